@@ -13,9 +13,19 @@ pub(crate) mod processing;
 
 #[cfg(feature = "processing")]
 pub use processing::{
-    Decimate, DecimationError, DecimationFilter, DecimationFilterType, Filter, FilterItem,
-    MaskError, MaskFilter, MaskOperand, Masking, Preprocessing, Repair, RepairTrait, Split,
-    TimeCorrection, TimeCorrectionError, TimeCorrectionsDB, Timeshift,
+    AnalysisWarning, Attribute, CalendarError, Carrier, CarrierError, Census, ClockError,
+    ClockQuantity, ClockValue, DataCensus, DayOfYear, DaysOfWeek, Decimate, DecimationError,
+    DecimationFilter, DecimationFilterType, Diagnostic, DiagnosticExpectation, EpochFlag,
+    Error as FilterError, Filter, FilterAnalysis, FilterExpr, FilterExprError, FilterItem,
+    FlagError, GeodeticPosition, InterpError, InterpFilter, InterpMethod, InterpSeries,
+    Interpolate, Interval, IntervalError, LLI_MAX, MaskError, MaskFilter, MaskOperand, MaskReport,
+    Masking, NavField, NavRange, NavValue, NavigationError, Observable, ObservableError,
+    OrbitClass, Physics, Pipeline, PipelineError, Preprocessing, Region, RegionError,
+    RelativeEpoch, RelativeError, RelativeSpan, Repair, RepairTrait, SNRTemplate, SNRTemplateError,
+    SSI_MAX, SatelliteDB, SatelliteError, SatelliteInfo, Smooth, SmoothingError, SmoothingFilter,
+    SmoothingType, Split, Step, TemplateInterpolation, TimeAnchor, TimeCorrection,
+    TimeCorrectionError, TimeCorrectionsDB, TimeOfDay, TimeOfWeek, TimeSpan, Timeshift,
+    lli_matches,
 };
 
 #[cfg(feature = "processing")]
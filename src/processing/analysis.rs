@@ -0,0 +1,558 @@
+//! Static analysis of [Filter] sets, prior their application
+use crate::processing::{
+    DecimationFilterType, Filter, FilterItem, Interval, MaskFilter, MaskOperand, SmoothingType,
+};
+
+use gnss_rs::prelude::{Constellation, SV};
+use hifitime::{Duration, Epoch};
+
+/// [AnalysisWarning]s are emitted by the [FilterAnalysis].
+/// Filters are designated by their index in the analyzed set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalysisWarning {
+    /// This filter has no effect and was removed
+    NoOp(usize),
+    /// These filters were merged into a single one
+    Merged(Vec<usize>),
+    /// These filters contradict each other: the resulting selection is provably empty
+    EmptySelection(Vec<usize>),
+}
+
+impl std::fmt::Display for AnalysisWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let list = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|i| format!("#{}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Self::NoOp(index) => write!(f, "filter #{} has no effect", index),
+            Self::Merged(indices) => write!(f, "filters {} were merged", list(indices)),
+            Self::EmptySelection(indices) => write!(
+                f,
+                "filters {} contradict each other: selection is empty",
+                list(indices)
+            ),
+        }
+    }
+}
+
+/// [FilterAnalysis] simplifies a set of [Filter]s, applied in order:
+/// - compatible [MaskFilter]s are merged together (like `e>10` and `e<60` into `e in (10, 60)`)
+/// - filters that have no effect are removed (like `e>=0` or `decim:1`)
+/// - provably empty selections are reported (like `=GPS` followed by `!=GPS`).
+///
+/// [MaskFilter]s are only merged with their neighbors: other filters
+/// (decimation, smoothing, interpolation) act as barriers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterAnalysis {
+    /// Simplified [Filter]s, in order of application
+    pub filters: Vec<Filter>,
+    /// [AnalysisWarning]s
+    pub warnings: Vec<AnalysisWarning>,
+}
+
+impl FilterAnalysis {
+    /// Runs the analysis of given [Filter]s, applied in order.
+    pub fn run(filters: &[Filter]) -> Self {
+        let mut analysis = Self::default();
+        let mut segment = Vec::<(usize, MaskFilter)>::new();
+
+        for (index, filter) in filters.iter().enumerate() {
+            if let Filter::Mask(mask) = filter {
+                segment.push((index, mask.clone()));
+            } else if is_noop(filter) {
+                // removed: not a barrier
+                analysis.warnings.push(AnalysisWarning::NoOp(index));
+            } else {
+                analysis.flush(&mut segment);
+                analysis.filters.push(filter.clone());
+            }
+        }
+
+        analysis.flush(&mut segment);
+        analysis
+    }
+
+    /// Returns true if the resulting selection is provably empty
+    pub fn is_empty_selection(&self) -> bool {
+        self.warnings
+            .iter()
+            .any(|w| matches!(w, AnalysisWarning::EmptySelection(_)))
+    }
+
+    /// Simplifies a set of consecutive [MaskFilter]s
+    fn flush(&mut self, segment: &mut Vec<(usize, MaskFilter)>) {
+        let mut elevation = Bounds::<f64>::default();
+        let mut azimuth = Bounds::<f64>::default();
+        let mut snr = Bounds::<f64>::default();
+        let mut epoch = Bounds::<Epoch>::default();
+        let mut constellations = Selection::<Constellation>::default();
+        let mut svs = Selection::<SV>::default();
+
+        // (position, masks) to be emitted, where position is the index of the first source
+        let mut output = Vec::<(usize, Vec<MaskFilter>)>::new();
+
+        for (index, mask) in segment.drain(..) {
+            let handled = match &mask.item {
+                FilterItem::ElevationItem(v) => elevation.restrict(index, &mask, *v),
+                FilterItem::AzimuthItem(v) => azimuth.restrict(index, &mask, *v),
+                FilterItem::SNRItem(v) => snr.restrict(index, &mask, *v),
+                FilterItem::EpochItem(t) => epoch.restrict(index, &mask, *t),
+                FilterItem::ElevationRangeItem(i) => elevation.restrict_interval(index, &mask, i),
+                FilterItem::AzimuthRangeItem(i) => azimuth.restrict_interval(index, &mask, i),
+                FilterItem::SNRRangeItem(i) => snr.restrict_interval(index, &mask, i),
+                FilterItem::EpochRangeItem(i) => epoch.restrict_interval(index, &mask, i),
+                FilterItem::ConstellationItem(c) => constellations.restrict(index, &mask, c),
+                FilterItem::SvItem(sv) => svs.restrict(index, &mask, sv),
+                _ => false,
+            };
+
+            if handled {
+                continue;
+            }
+
+            // other masks are only folded when strictly identical
+            let duplicate = output
+                .iter()
+                .find(|(_, masks)| masks.len() == 1 && masks[0] == mask)
+                .map(|(position, _)| *position);
+
+            match duplicate {
+                Some(position) => self
+                    .warnings
+                    .push(AnalysisWarning::Merged(vec![position, index])),
+                None => output.push((index, vec![mask])),
+            }
+        }
+
+        self.flush_bounds(
+            &mut output,
+            elevation,
+            Some((0.0, 90.0)),
+            FilterItem::ElevationItem,
+            FilterItem::ElevationRangeItem,
+        );
+        self.flush_bounds(
+            &mut output,
+            azimuth,
+            Some((0.0, 360.0)),
+            FilterItem::AzimuthItem,
+            FilterItem::AzimuthRangeItem,
+        );
+        self.flush_bounds(
+            &mut output,
+            snr,
+            None,
+            FilterItem::SNRItem,
+            FilterItem::SNRRangeItem,
+        );
+        self.flush_bounds(
+            &mut output,
+            epoch,
+            None,
+            FilterItem::EpochItem,
+            FilterItem::EpochRangeItem,
+        );
+
+        // satellites should also comply with the constellation selection
+        let mut sv_retained = svs.retained();
+        if let Some(retained) = sv_retained.as_mut() {
+            if let Some(allowed) = constellations.retained() {
+                retained.retain(|sv| allowed.contains(&sv.constellation));
+            }
+            retained.retain(|sv| !constellations.exclude.contains(&sv.constellation));
+        }
+
+        let selection_empty = constellations.retained().is_some_and(|c| c.is_empty())
+            || sv_retained.as_ref().is_some_and(|sv| sv.is_empty());
+
+        if selection_empty {
+            let mut sources = constellations.sources.clone();
+            sources.extend(svs.sources.iter());
+            sources.sort();
+            self.warnings.push(AnalysisWarning::EmptySelection(sources));
+            for (position, mask) in constellations.originals.into_iter() {
+                output.push((position, vec![mask]));
+            }
+            for (position, mask) in svs.originals.into_iter() {
+                output.push((position, vec![mask]));
+            }
+        } else {
+            self.flush_selection(&mut output, &constellations, constellations.retained());
+            self.flush_selection(&mut output, &svs, sv_retained);
+        }
+
+        output.sort_by_key(|(position, _)| *position);
+        for (_, masks) in output {
+            self.filters.extend(masks.into_iter().map(Filter::Mask));
+        }
+    }
+
+    fn flush_bounds<T: PartialOrd + Copy>(
+        &mut self,
+        output: &mut Vec<(usize, Vec<MaskFilter>)>,
+        bounds: Bounds<T>,
+        domain: Option<(T, T)>,
+        scalar: fn(T) -> FilterItem,
+        range: fn(Interval<T>) -> FilterItem,
+    ) {
+        let Some(position) = bounds.sources.first().copied() else {
+            return;
+        };
+
+        if bounds.is_empty() {
+            self.warnings
+                .push(AnalysisWarning::EmptySelection(bounds.sources.clone()));
+            output.push((position, bounds.originals));
+        } else if domain.is_some_and(|(min, max)| bounds.covers(min, max)) {
+            for index in bounds.sources.iter() {
+                self.warnings.push(AnalysisWarning::NoOp(*index));
+            }
+        } else {
+            if bounds.sources.len() > 1 {
+                self.warnings
+                    .push(AnalysisWarning::Merged(bounds.sources.clone()));
+            }
+            output.push((position, bounds.mask(scalar, range).into_iter().collect()));
+        }
+    }
+
+    fn flush_selection<T: Clone + PartialEq>(
+        &mut self,
+        output: &mut Vec<(usize, Vec<MaskFilter>)>,
+        selection: &Selection<T>,
+        retained: Option<Vec<T>>,
+    ) where
+        FilterItem: From<Vec<T>>,
+    {
+        let Some(position) = selection.sources.first().copied() else {
+            return;
+        };
+
+        if selection.sources.len() > 1 {
+            self.warnings
+                .push(AnalysisWarning::Merged(selection.sources.clone()));
+        }
+
+        let mask = match retained {
+            Some(retained) => MaskFilter {
+                operand: MaskOperand::Equals,
+                item: retained.into(),
+            },
+            None => MaskFilter {
+                operand: MaskOperand::NotEquals,
+                item: selection.exclude.clone().into(),
+            },
+        };
+
+        output.push((position, vec![mask]));
+    }
+}
+
+/// Returns true if this (non mask) [Filter] has no effect
+fn is_noop(filter: &Filter) -> bool {
+    match filter {
+        Filter::Decimation(decim) => match decim.filter {
+            DecimationFilterType::Modulo(modulo) => modulo <= 1,
+            DecimationFilterType::Duration(dt) => dt <= Duration::ZERO,
+        },
+        Filter::Smoothing(smoothing) => match smoothing.stype {
+            SmoothingType::MovingAverage(dt) => dt <= Duration::ZERO,
+            SmoothingType::Hatch => false,
+        },
+        _ => false,
+    }
+}
+
+/// Bounds of a scalar domain, obtained by successive comparisons
+#[derive(Debug, Clone)]
+struct Bounds<T> {
+    /// Lower bound, and whether it is inclusive
+    lower: Option<(T, bool)>,
+    /// Upper bound, and whether it is inclusive
+    upper: Option<(T, bool)>,
+    /// Index of the contributing filters
+    sources: Vec<usize>,
+    /// Contributing filters
+    originals: Vec<MaskFilter>,
+}
+
+impl<T> Default for Bounds<T> {
+    fn default() -> Self {
+        Self {
+            lower: None,
+            upper: None,
+            sources: Vec::new(),
+            originals: Vec::new(),
+        }
+    }
+}
+
+impl<T: PartialOrd + Copy> Bounds<T> {
+    fn restrict_lower(&mut self, value: T, inclusive: bool) {
+        let tighter = match self.lower {
+            Some((lower, lower_inclusive)) => {
+                value > lower || (value == lower && lower_inclusive && !inclusive)
+            }
+            None => true,
+        };
+        if tighter {
+            self.lower = Some((value, inclusive));
+        }
+    }
+
+    fn restrict_upper(&mut self, value: T, inclusive: bool) {
+        let tighter = match self.upper {
+            Some((upper, upper_inclusive)) => {
+                value < upper || (value == upper && upper_inclusive && !inclusive)
+            }
+            None => true,
+        };
+        if tighter {
+            self.upper = Some((value, inclusive));
+        }
+    }
+
+    /// Restricts these [Bounds], returns false when the operand does not apply
+    fn restrict(&mut self, index: usize, mask: &MaskFilter, value: T) -> bool {
+        match mask.operand {
+            MaskOperand::GreaterThan => self.restrict_lower(value, false),
+            MaskOperand::GreaterEquals => self.restrict_lower(value, true),
+            MaskOperand::LowerThan => self.restrict_upper(value, false),
+            MaskOperand::LowerEquals => self.restrict_upper(value, true),
+            MaskOperand::Equals => {
+                self.restrict_lower(value, true);
+                self.restrict_upper(value, true);
+            }
+            _ => return false,
+        }
+        self.sources.push(index);
+        self.originals.push(mask.clone());
+        true
+    }
+
+    /// Restricts these [Bounds] to [Interval], returns false when the operand does not apply
+    fn restrict_interval(
+        &mut self,
+        index: usize,
+        mask: &MaskFilter,
+        interval: &Interval<T>,
+    ) -> bool {
+        if mask.operand != MaskOperand::Inside {
+            return false;
+        }
+        self.restrict_lower(interval.start, interval.start_inclusive);
+        self.restrict_upper(interval.end, interval.end_inclusive);
+        self.sources.push(index);
+        self.originals.push(mask.clone());
+        true
+    }
+
+    fn is_empty(&self) -> bool {
+        match (self.lower, self.upper) {
+            (Some((lower, lower_inclusive)), Some((upper, upper_inclusive))) => {
+                lower > upper || (lower == upper && !(lower_inclusive && upper_inclusive))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if these [Bounds] cover the entire [min, max] domain
+    fn covers(&self, min: T, max: T) -> bool {
+        let lower = self
+            .lower
+            .is_none_or(|(lower, inclusive)| lower < min || (lower == min && inclusive));
+        let upper = self
+            .upper
+            .is_none_or(|(upper, inclusive)| upper > max || (upper == max && inclusive));
+        lower && upper
+    }
+
+    /// Expresses these [Bounds] as a single [MaskFilter]
+    fn mask(
+        &self,
+        scalar: impl Fn(T) -> FilterItem,
+        range: impl Fn(Interval<T>) -> FilterItem,
+    ) -> Option<MaskFilter> {
+        let (operand, item) = match (self.lower, self.upper) {
+            (Some((lower, true)), Some((upper, true))) if lower == upper => {
+                (MaskOperand::Equals, scalar(lower))
+            }
+            (Some((start, start_inclusive)), Some((end, end_inclusive))) => (
+                MaskOperand::Inside,
+                range(Interval {
+                    start,
+                    end,
+                    start_inclusive,
+                    end_inclusive,
+                }),
+            ),
+            (Some((lower, true)), None) => (MaskOperand::GreaterEquals, scalar(lower)),
+            (Some((lower, false)), None) => (MaskOperand::GreaterThan, scalar(lower)),
+            (None, Some((upper, true))) => (MaskOperand::LowerEquals, scalar(upper)),
+            (None, Some((upper, false))) => (MaskOperand::LowerThan, scalar(upper)),
+            (None, None) => return None,
+        };
+        Some(MaskFilter { operand, item })
+    }
+}
+
+/// Selection of discrete values, obtained by successive equalities
+#[derive(Debug, Clone)]
+struct Selection<T> {
+    /// Retained values, when restricted by equalities
+    retain: Option<Vec<T>>,
+    /// Excluded values
+    exclude: Vec<T>,
+    /// Index of the contributing filters
+    sources: Vec<usize>,
+    /// Contributing filters
+    originals: Vec<(usize, MaskFilter)>,
+}
+
+impl<T> Default for Selection<T> {
+    fn default() -> Self {
+        Self {
+            retain: None,
+            exclude: Vec::new(),
+            sources: Vec::new(),
+            originals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Selection<T> {
+    /// Restricts this [Selection], returns false when the operand does not apply
+    fn restrict(&mut self, index: usize, mask: &MaskFilter, values: &[T]) -> bool {
+        match mask.operand {
+            MaskOperand::Equals => {
+                let retained = match self.retain.take() {
+                    Some(retained) => retained
+                        .into_iter()
+                        .filter(|value| values.contains(value))
+                        .collect(),
+                    None => values.to_vec(),
+                };
+                self.retain = Some(retained);
+            }
+            MaskOperand::NotEquals => {
+                for value in values {
+                    if !self.exclude.contains(value) {
+                        self.exclude.push(value.clone());
+                    }
+                }
+            }
+            _ => return false,
+        }
+        self.sources.push(index);
+        self.originals.push((index, mask.clone()));
+        true
+    }
+
+    /// Returns the retained values, when restricted by equalities
+    fn retained(&self) -> Option<Vec<T>> {
+        self.retain.as_ref().map(|retained| {
+            retained
+                .iter()
+                .filter(|value| !self.exclude.contains(value))
+                .cloned()
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn filters(descriptors: &[&str]) -> Vec<Filter> {
+        descriptors
+            .iter()
+            .map(|desc| Filter::from_str(desc).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn analysis_contradictions() {
+        for (descriptors, sources) in [
+            (vec!["=GPS", "!=GPS"], vec![0, 1]),
+            (vec!["e>60", "GAL", "e<30"], vec![0, 2]),
+            (vec!["=G08,G09", "=GAL"], vec![0, 1]),
+            (vec!["snr in 30..40", "snr>=40"], vec![0, 1]),
+            (
+                vec![">2020-01-02T00:00:00 UTC", "<=2020-01-01T00:00:00 UTC"],
+                vec![0, 1],
+            ),
+        ] {
+            let analysis = FilterAnalysis::run(&filters(&descriptors));
+            assert!(analysis.is_empty_selection(), "for {:?}", descriptors);
+            assert!(
+                analysis
+                    .warnings
+                    .contains(&AnalysisWarning::EmptySelection(sources)),
+                "for {:?}: {:?}",
+                descriptors,
+                analysis.warnings
+            );
+            // contradicted filters are preserved
+            assert_eq!(
+                analysis.filters.len(),
+                descriptors.len(),
+                "for {:?}",
+                descriptors
+            );
+        }
+
+        // barrier
+        let analysis = FilterAnalysis::run(&filters(&[
+            "e>60",
+            "interp:linear:2020-01-01T00:00:00 UTC",
+            "e<30",
+        ]));
+        assert!(!analysis.is_empty_selection());
+    }
+
+    #[test]
+    fn analysis_simplification() {
+        for (descriptors, expected, warnings) in [
+            (
+                vec!["e>10", "e<60", "e>20"],
+                vec!["e in (20, 60)"],
+                vec![AnalysisWarning::Merged(vec![0, 1, 2])],
+            ),
+            (
+                vec!["=GPS,GAL", "decim:1", "!=GAL", "e>=0"],
+                vec!["=GPS"],
+                vec![
+                    AnalysisWarning::NoOp(1),
+                    AnalysisWarning::NoOp(3),
+                    AnalysisWarning::Merged(vec![0, 2]),
+                ],
+            ),
+            (
+                vec!["iode", "G08,G09", "decim:30 s", "iode", "!=G09", "iode"],
+                vec!["iode", "=G08,G09", "decim:30 s", "iode", "!=G09"],
+                vec![AnalysisWarning::Merged(vec![3, 5])],
+            ),
+            (
+                vec!["snr>=30", "snr>=20", "snr<=30"],
+                vec!["snr=30"],
+                vec![AnalysisWarning::Merged(vec![0, 1, 2])],
+            ),
+        ] {
+            let analysis = FilterAnalysis::run(&filters(&descriptors));
+            assert!(!analysis.is_empty_selection(), "for {:?}", descriptors);
+            assert_eq!(
+                analysis.filters,
+                filters(&expected),
+                "for {:?}",
+                descriptors
+            );
+            assert_eq!(analysis.warnings, warnings, "for {:?}", descriptors);
+        }
+    }
+}
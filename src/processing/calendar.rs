@@ -0,0 +1,552 @@
+//! Calendar items, either recurring (like time of day and day of week),
+//! or describing GNSS calendar periods (like week numbers)
+use std::str::FromStr;
+use thiserror::Error;
+
+use gnss_rs::prelude::Constellation;
+use hifitime::{Duration, Epoch, TimeScale, Unit, Weekday};
+
+use crate::processing::Interval;
+
+/// Calendar items parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid time of day \"{0}\"")]
+    InvalidTimeOfDay(String),
+    #[error("unknown day of week \"{0}\"")]
+    UnknownDayOfWeek(String),
+    #[error("invalid day of year \"{0}\"")]
+    InvalidDayOfYear(String),
+    #[error("invalid time of week \"{0}\"")]
+    InvalidTimeOfWeek(String),
+    #[error("invalid week number \"{0}\"")]
+    InvalidWeek(String),
+    #[error("invalid modified julian date \"{0}\"")]
+    InvalidMJD(String),
+}
+
+/// Returns the gregorian date and the elapsed time within that day,
+/// for this [Epoch] expressed in given [TimeScale].
+fn date_and_time(epoch: &Epoch, timescale: TimeScale) -> ((i32, u8, u8), Duration) {
+    let (year, month, day, hours, minutes, seconds, nanos) = epoch.to_gregorian(timescale);
+    let seconds = (hours as i128 * 60 + minutes as i128) * 60 + seconds as i128;
+    (
+        (year, month, day),
+        Duration::from_total_nanoseconds(seconds * 1_000_000_000 + nanos as i128),
+    )
+}
+
+/// Parses an optional trailing [TimeScale], or [Constellation] that designates its own [TimeScale].
+/// Returns the remaining description and the [TimeScale].
+pub(crate) fn split_timescale(content: &str, default: TimeScale) -> (&str, TimeScale) {
+    let c = content.trim();
+    c.rsplit_once(char::is_whitespace)
+        .and_then(|(rem, ts)| {
+            TimeScale::from_str(ts)
+                .ok()
+                .or_else(|| Constellation::from_str(ts).ok()?.timescale())
+                .map(|timescale| (rem.trim(), timescale))
+        })
+        .unwrap_or((c, default))
+}
+
+/// Returns the [Epoch] of given Modified Julian Date, in given [TimeScale]
+pub(crate) fn mjd_epoch(mjd: f64, timescale: TimeScale) -> Epoch {
+    Epoch::from_mjd_in_time_scale(mjd, timescale)
+}
+
+/// Returns the period covering an entire Modified Julian Day, in given [TimeScale]
+pub(crate) fn mjd_period(mjd: i64, timescale: TimeScale) -> Interval<Epoch> {
+    Interval::new(
+        mjd_epoch(mjd as f64, timescale),
+        mjd_epoch((mjd + 1) as f64, timescale),
+    )
+}
+
+/// Returns the period covering an entire week, in given [TimeScale].
+/// Weeks are counted from the reference epoch of the [TimeScale].
+pub(crate) fn week_period(week: u32, timescale: TimeScale) -> Interval<Epoch> {
+    Interval::new(
+        Epoch::from_time_of_week(week, 0, timescale),
+        Epoch::from_time_of_week(week.saturating_add(1), 0, timescale),
+    )
+}
+
+/// Returns the period covering an entire day of year, in given [TimeScale].
+/// Days are counted from 1.
+pub(crate) fn doy_period(year: i32, doy: u16, timescale: TimeScale) -> Option<Interval<Epoch>> {
+    let start = Epoch::from_day_of_year(year, doy as f64, timescale);
+    if doy == 0 || date_and_time(&start, timescale).0.0 != year {
+        return None;
+    }
+    Some(Interval::new(
+        start,
+        Epoch::from_day_of_year(year, (doy + 1) as f64, timescale),
+    ))
+}
+
+/// Converts an [Interval] of period numbers (like weeks) into the [Interval]
+/// of [Epoch]s they cover. `period` returns the [Interval] covered by a single period.
+pub(crate) fn periods_interval(
+    interval: &Interval<i64>,
+    period: impl Fn(i64) -> Interval<Epoch>,
+) -> Interval<Epoch> {
+    let start = if interval.start_inclusive {
+        period(interval.start).start
+    } else {
+        period(interval.start).end
+    };
+    let end = if interval.end_inclusive {
+        period(interval.end).end
+    } else {
+        period(interval.end).start
+    };
+    Interval::new(start, end)
+}
+
+/// [TimeOfDay] is the time elapsed since midnight, in a given [TimeScale].
+/// It is described as `HH:MM[:SS[.sss]] [TIMESCALE]`, like `22:00 UTC` or `06:30:15 GPST`,
+/// the [TimeScale] being [TimeScale::UTC] when omitted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimeOfDay {
+    /// Elapsed time since midnight
+    pub time: Duration,
+    /// [TimeScale] in which this [TimeOfDay] is expressed
+    pub timescale: TimeScale,
+}
+
+impl TimeOfDay {
+    /// Builds a new [TimeOfDay]
+    pub fn new(hours: u8, minutes: u8, seconds: u8, timescale: TimeScale) -> Self {
+        Self {
+            time: hours as f64 * Unit::Hour
+                + minutes as f64 * Unit::Minute
+                + seconds as f64 * Unit::Second,
+            timescale,
+        }
+    }
+
+    /// Returns the [TimeOfDay] of this [Epoch], expressed in given [TimeScale].
+    pub fn from_epoch(epoch: &Epoch, timescale: TimeScale) -> Self {
+        Self {
+            time: date_and_time(epoch, timescale).1,
+            timescale,
+        }
+    }
+}
+
+impl PartialOrd for TimeOfDay {
+    /// [TimeOfDay]s may only be compared within the same [TimeScale]
+    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+        if self.timescale == rhs.timescale {
+            self.time.partial_cmp(&rhs.time)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let (time, timescale) = split_timescale(content, TimeScale::UTC);
+        let invalid = || Error::InvalidTimeOfDay(content.trim().to_string());
+
+        let fields = time.split(':').collect::<Vec<_>>();
+        if !(2..=3).contains(&fields.len()) {
+            return Err(invalid());
+        }
+
+        let hours = u8::from_str(fields[0]).map_err(|_| invalid())?;
+        let minutes = u8::from_str(fields[1]).map_err(|_| invalid())?;
+        let seconds = match fields.get(2) {
+            Some(seconds) => f64::from_str(seconds).map_err(|_| invalid())?,
+            None => 0.0,
+        };
+
+        if hours > 23 || minutes > 59 || !(0.0..60.0).contains(&seconds) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            time: hours as f64 * Unit::Hour
+                + minutes as f64 * Unit::Minute
+                + seconds * Unit::Second,
+            timescale,
+        })
+    }
+}
+
+impl std::fmt::Display for TimeOfDay {
+    /// Formats this [TimeOfDay] so it may be parsed back with [TimeOfDay::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let nanos = self.time.total_nanoseconds();
+        let (hours, nanos) = (nanos / 3_600_000_000_000, nanos % 3_600_000_000_000);
+        let (minutes, nanos) = (nanos / 60_000_000_000, nanos % 60_000_000_000);
+        let (seconds, nanos) = (nanos / 1_000_000_000, nanos % 1_000_000_000);
+
+        write!(f, "{:02}:{:02}", hours, minutes)?;
+        if nanos > 0 {
+            let fraction = format!("{:09}", nanos);
+            write!(f, ":{:02}.{}", seconds, fraction.trim_end_matches('0'))?;
+        } else if seconds > 0 {
+            write!(f, ":{:02}", seconds)?;
+        }
+        write!(f, " {}", self.timescale)
+    }
+}
+
+/// Returns the short name of a [Weekday]
+fn weekday_name(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "mon",
+        Weekday::Tuesday => "tue",
+        Weekday::Wednesday => "wed",
+        Weekday::Thursday => "thu",
+        Weekday::Friday => "fri",
+        Weekday::Saturday => "sat",
+        Weekday::Sunday => "sun",
+    }
+}
+
+/// [DaysOfWeek] is a selection of [Weekday]s, in a given [TimeScale].
+/// It is described as a comma separated list with an optional [TimeScale],
+/// like `sat,sun` or `mon, friday GPST`, the [TimeScale] being [TimeScale::UTC] when omitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaysOfWeek {
+    /// Selected [Weekday]s
+    pub days: Vec<Weekday>,
+    /// [TimeScale] in which the days are expressed
+    pub timescale: TimeScale,
+}
+
+impl DaysOfWeek {
+    /// Returns the [Weekday] of this [Epoch], expressed in given [TimeScale].
+    pub fn weekday(epoch: &Epoch, timescale: TimeScale) -> Weekday {
+        // the calendar day is defined in given timescale, its weekday does not depend on it:
+        // evaluate it in TAI, whose reference epoch (J1900) was a Monday
+        let ((year, month, day), _) = date_and_time(epoch, timescale);
+        Epoch::from_gregorian_at_midnight(year, month, day, TimeScale::TAI)
+            .weekday_in_time_scale(TimeScale::TAI)
+    }
+
+    /// Returns true if this [Epoch] falls within one of the selected days
+    pub fn contains(&self, epoch: &Epoch) -> bool {
+        self.days.contains(&Self::weekday(epoch, self.timescale))
+    }
+}
+
+impl PartialOrd for DaysOfWeek {
+    /// [DaysOfWeek] may only be compared within the same [TimeScale]
+    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+        if self.timescale == rhs.timescale {
+            self.days.partial_cmp(&rhs.days)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for DaysOfWeek {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let (days, timescale) = split_timescale(content, TimeScale::UTC);
+        let days = days
+            .split(',')
+            .map(|day| {
+                Weekday::from_str(&day.trim().to_ascii_lowercase())
+                    .map_err(|_| Error::UnknownDayOfWeek(day.trim().to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { days, timescale })
+    }
+}
+
+impl std::fmt::Display for DaysOfWeek {
+    /// Formats these [DaysOfWeek] so they may be parsed back with [DaysOfWeek::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let days = self.days.iter().map(weekday_name).collect::<Vec<_>>();
+        write!(f, "{} {}", days.join(","), self.timescale)
+    }
+}
+
+/// [DayOfYear] is a recurring day of year, in a given [TimeScale], counted from 1.
+/// It is described as `DDD [TIMESCALE]`, like `045` or `045 GPST`,
+/// the [TimeScale] being [TimeScale::UTC] when omitted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DayOfYear {
+    /// Day of year, within [1, 366]
+    pub day: u16,
+    /// [TimeScale] in which this [DayOfYear] is expressed
+    pub timescale: TimeScale,
+}
+
+impl DayOfYear {
+    /// Returns the [DayOfYear] of this [Epoch], expressed in given [TimeScale].
+    pub fn from_epoch(epoch: &Epoch, timescale: TimeScale) -> Self {
+        let ((year, month, day), _) = date_and_time(epoch, timescale);
+        let elapsed = Epoch::from_gregorian_at_midnight(year, month, day, timescale)
+            - Epoch::from_gregorian_at_midnight(year, 1, 1, timescale);
+        Self {
+            day: elapsed.to_unit(Unit::Day).round() as u16 + 1,
+            timescale,
+        }
+    }
+}
+
+impl PartialOrd for DayOfYear {
+    /// [DayOfYear]s may only be compared within the same [TimeScale]
+    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+        if self.timescale == rhs.timescale {
+            self.day.partial_cmp(&rhs.day)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for DayOfYear {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let (day, timescale) = split_timescale(content, TimeScale::UTC);
+        match u16::from_str(day) {
+            Ok(day) if (1..=366).contains(&day) => Ok(Self { day, timescale }),
+            _ => Err(Error::InvalidDayOfYear(content.trim().to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for DayOfYear {
+    /// Formats this [DayOfYear] so it may be parsed back with [DayOfYear::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:03} {}", self.day, self.timescale)
+    }
+}
+
+/// [TimeOfWeek] is a recurring time of week, in a given [TimeScale].
+/// It is described in seconds, or as a duration, with an optional [TimeScale]:
+/// like `86400`, `1 d` or `3600 BDT`, the [TimeScale] being [TimeScale::GPST] when omitted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimeOfWeek {
+    /// Elapsed time since the start of week
+    pub time: Duration,
+    /// [TimeScale] in which this [TimeOfWeek] is expressed
+    pub timescale: TimeScale,
+}
+
+impl TimeOfWeek {
+    /// Returns the [TimeOfWeek] of this [Epoch], expressed in given [TimeScale].
+    pub fn from_epoch(epoch: &Epoch, timescale: TimeScale) -> Self {
+        let (_, nanos) = epoch.to_time_scale(timescale).to_time_of_week();
+        Self {
+            time: Duration::from_total_nanoseconds(nanos as i128),
+            timescale,
+        }
+    }
+}
+
+impl PartialOrd for TimeOfWeek {
+    /// [TimeOfWeek]s may only be compared within the same [TimeScale]
+    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+        if self.timescale == rhs.timescale {
+            self.time.partial_cmp(&rhs.time)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for TimeOfWeek {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let (time, timescale) = split_timescale(content, TimeScale::GPST);
+        let time = match f64::from_str(time) {
+            Ok(seconds) => seconds * Unit::Second,
+            Err(_) => Duration::from_str(time)
+                .map_err(|_| Error::InvalidTimeOfWeek(content.trim().to_string()))?,
+        };
+        if time < Duration::ZERO || time >= 7.0 * Unit::Day {
+            return Err(Error::InvalidTimeOfWeek(content.trim().to_string()));
+        }
+        Ok(Self { time, timescale })
+    }
+}
+
+impl std::fmt::Display for TimeOfWeek {
+    /// Formats this [TimeOfWeek] so it may be parsed back with [TimeOfWeek::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.time.to_seconds(), self.timescale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_of_day() {
+        let tod = TimeOfDay::from_str("22:00 UTC").unwrap();
+        assert_eq!(tod, TimeOfDay::new(22, 0, 0, TimeScale::UTC));
+        assert_eq!(TimeOfDay::from_str("22:00").unwrap(), tod);
+        assert!(TimeOfDay::new(23, 0, 0, TimeScale::UTC) > tod);
+        assert!(
+            TimeOfDay::new(23, 0, 0, TimeScale::GPST)
+                .partial_cmp(&tod)
+                .is_none()
+        );
+
+        for desc in ["22:00 UTC", "06:30:15 GPST", "00:00:00.5 GST"] {
+            let tod = TimeOfDay::from_str(desc).unwrap();
+            assert_eq!(tod.to_string(), desc);
+        }
+        for desc in ["24:00", "12:60", "12", "12:00 XYZ", "12:00:61"] {
+            assert!(TimeOfDay::from_str(desc).is_err(), "parsed \"{}\"", desc);
+        }
+
+        let epoch = Epoch::from_str("2020-06-25T06:30:15 UTC").unwrap();
+        assert_eq!(
+            TimeOfDay::from_epoch(&epoch, TimeScale::UTC),
+            TimeOfDay::new(6, 30, 15, TimeScale::UTC)
+        );
+
+        let epoch = Epoch::from_str("2020-06-25T06:30:34 TAI").unwrap();
+        assert_eq!(
+            TimeOfDay::from_epoch(&epoch, TimeScale::GPST),
+            TimeOfDay::new(6, 30, 15, TimeScale::GPST)
+        );
+        assert_eq!(
+            TimeOfDay::from_epoch(&epoch, TimeScale::BDT),
+            TimeOfDay::new(6, 30, 1, TimeScale::BDT)
+        );
+
+        for timescale in [TimeScale::ET, TimeScale::TDB] {
+            let epoch = Epoch::from_str(&format!("2020-06-25T06:30:15 {}", timescale)).unwrap();
+            // dynamical timescales do not round trip to the nanosecond
+            let tod = TimeOfDay::from_epoch(&epoch, timescale);
+            let error = tod.time - TimeOfDay::new(6, 30, 15, timescale).time;
+            assert!(
+                error.abs() < 1.0 * Unit::Microsecond,
+                "{} in {}",
+                tod,
+                timescale
+            );
+        }
+    }
+
+    #[test]
+    fn days_of_week() {
+        let weekend = DaysOfWeek::from_str("sat, Sun").unwrap();
+        assert_eq!(weekend.days, vec![Weekday::Saturday, Weekday::Sunday]);
+        assert_eq!(weekend.timescale, TimeScale::UTC);
+        assert_eq!(weekend.to_string(), "sat,sun UTC");
+
+        let days = DaysOfWeek::from_str("monday,friday GPST").unwrap();
+        assert_eq!(days.timescale, TimeScale::GPST);
+        assert!(DaysOfWeek::from_str("sat,sunday2").is_err());
+
+        // 2020-06-27 is a saturday
+        let saturday = Epoch::from_str("2020-06-27T12:00:00 UTC").unwrap();
+        assert!(weekend.contains(&saturday));
+        assert_eq!(
+            DaysOfWeek::weekday(&saturday, TimeScale::GPST),
+            Weekday::Saturday
+        );
+        let friday = Epoch::from_str("2020-06-26T23:59:59 UTC").unwrap();
+        assert!(!weekend.contains(&friday));
+        // though it is already saturday in GPST
+        assert_eq!(
+            DaysOfWeek::weekday(&friday, TimeScale::GPST),
+            Weekday::Saturday
+        );
+
+        let midnight = Epoch::from_str("2020-06-27T00:00:20 TAI").unwrap();
+        for (timescale, weekday) in [
+            (TimeScale::TAI, Weekday::Saturday),
+            (TimeScale::GST, Weekday::Saturday),
+            (TimeScale::BDT, Weekday::Friday),
+        ] {
+            assert_eq!(
+                DaysOfWeek::weekday(&midnight, timescale),
+                weekday,
+                "in {}",
+                timescale
+            );
+        }
+
+        for timescale in [TimeScale::ET, TimeScale::TDB] {
+            let saturday = Epoch::from_str(&format!("2020-06-27T00:00:01 {}", timescale)).unwrap();
+            assert_eq!(
+                DaysOfWeek::weekday(&saturday, timescale),
+                Weekday::Saturday,
+                "in {}",
+                timescale
+            );
+        }
+    }
+
+    #[test]
+    fn calendar_periods() {
+        let utc = |desc: &str| Epoch::from_str(desc).unwrap();
+
+        let week = week_period(2310, TimeScale::GPST);
+        assert_eq!(week.start, utc("2024-04-14T00:00:00 GPST"));
+        assert_eq!(week.end, utc("2024-04-21T00:00:00 GPST"));
+
+        assert_eq!(
+            mjd_epoch(60000.0, TimeScale::UTC),
+            utc("2023-02-25T00:00:00 UTC")
+        );
+        assert_eq!(
+            mjd_epoch(60000.5, TimeScale::UTC),
+            utc("2023-02-25T12:00:00 UTC")
+        );
+        assert_eq!(
+            mjd_period(60000, TimeScale::UTC).end,
+            utc("2023-02-26T00:00:00 UTC")
+        );
+
+        let doy = doy_period(2024, 45, TimeScale::UTC).unwrap();
+        assert_eq!(doy.start, utc("2024-02-14T00:00:00 UTC"));
+        assert!(doy_period(2024, 366, TimeScale::UTC).is_some());
+        assert!(doy_period(2023, 366, TimeScale::UTC).is_none());
+
+        let weeks = Interval::<i64>::from_str("2310..=2311").unwrap();
+        let weeks = periods_interval(&weeks, |week| week_period(week as u32, TimeScale::GPST));
+        assert_eq!(weeks.start, utc("2024-04-14T00:00:00 GPST"));
+        assert_eq!(weeks.end, utc("2024-04-28T00:00:00 GPST"));
+    }
+
+    #[test]
+    fn recurring_periods() {
+        let doy = DayOfYear::from_str("045").unwrap();
+        assert_eq!(doy.day, 45);
+        assert_eq!(doy.to_string(), "045 UTC");
+        assert_eq!(
+            DayOfYear::from_epoch(
+                &Epoch::from_str("2024-02-14T12:00:00 UTC").unwrap(),
+                TimeScale::UTC
+            ),
+            doy
+        );
+        assert!(DayOfYear::from_str("367").is_err());
+
+        let tow = TimeOfWeek::from_str("86400").unwrap();
+        assert_eq!(tow.time, 1.0 * Unit::Day);
+        assert_eq!(tow.timescale, TimeScale::GPST);
+        assert_eq!(TimeOfWeek::from_str(&tow.to_string()).unwrap(), tow);
+        assert_eq!(
+            TimeOfWeek::from_str("1 d BDS").unwrap().timescale,
+            TimeScale::BDT
+        );
+        assert_eq!(
+            TimeOfWeek::from_epoch(
+                &Epoch::from_str("2024-04-15T00:00:00 GPST").unwrap(),
+                TimeScale::GPST
+            ),
+            tow
+        );
+        for desc in ["-1", "8 d", "1 x"] {
+            assert!(TimeOfWeek::from_str(desc).is_err(), "parsed \"{}\"", desc);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! GNSS carrier signals (frequency bands)
+use gnss_rs::prelude::Constellation;
+use thiserror::Error;
+
+/// [Carrier] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown carrier \"{0}\"")]
+    UnknownCarrier(String),
+}
+
+/// [Carrier] signal, described by its usual name, like `L1`, `E5a` or `B1I`.
+/// Different names may designate the same frequency, when the signal
+/// is transmitted by several [Constellation]s (like `L5`, `E5a` and `B2a`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Carrier {
+    /// GPS, QZSS and SBAS L1 (1575.42 MHz)
+    L1,
+    /// GPS and QZSS L2 (1227.60 MHz)
+    L2,
+    /// GPS, QZSS, SBAS and IRNSS L5 (1176.45 MHz)
+    L5,
+    /// QZSS L6 (1278.75 MHz)
+    L6,
+    /// Galileo E1 (1575.42 MHz)
+    E1,
+    /// Galileo E5a (1176.45 MHz)
+    E5a,
+    /// Galileo E5b (1207.14 MHz)
+    E5b,
+    /// Galileo E5 AltBOC (1191.795 MHz)
+    E5,
+    /// Galileo E6 (1278.75 MHz)
+    E6,
+    /// BeiDou B1I (1561.098 MHz)
+    B1I,
+    /// BeiDou B1C (1575.42 MHz)
+    B1C,
+    /// BeiDou B2a (1176.45 MHz)
+    B2a,
+    /// BeiDou B2b (1207.14 MHz)
+    B2b,
+    /// BeiDou B3I (1268.52 MHz)
+    B3I,
+    /// Glonass G1 FDMA (1602 MHz, center frequency)
+    G1,
+    /// Glonass G2 FDMA (1246 MHz, center frequency)
+    G2,
+    /// Glonass G3 CDMA (1202.025 MHz)
+    G3,
+    /// IRNSS S band (2492.028 MHz)
+    S,
+}
+
+impl Carrier {
+    /// All known [Carrier]s
+    pub const ALL: [Self; 18] = [
+        Self::L1,
+        Self::L2,
+        Self::L5,
+        Self::L6,
+        Self::E1,
+        Self::E5a,
+        Self::E5b,
+        Self::E5,
+        Self::E6,
+        Self::B1I,
+        Self::B1C,
+        Self::B2a,
+        Self::B2b,
+        Self::B3I,
+        Self::G1,
+        Self::G2,
+        Self::G3,
+        Self::S,
+    ];
+
+    /// Returns the center frequency of this [Carrier], in MHz.
+    pub fn frequency_mhz(&self) -> f64 {
+        match self {
+            Self::L1 | Self::E1 | Self::B1C => 1575.42,
+            Self::L2 => 1227.60,
+            Self::L5 | Self::E5a | Self::B2a => 1176.45,
+            Self::L6 | Self::E6 => 1278.75,
+            Self::E5b | Self::B2b => 1207.14,
+            Self::E5 => 1191.795,
+            Self::B1I => 1561.098,
+            Self::B3I => 1268.52,
+            Self::G1 => 1602.0,
+            Self::G2 => 1246.0,
+            Self::G3 => 1202.025,
+            Self::S => 2492.028,
+        }
+    }
+
+    /// Returns the center frequency of this [Carrier], in Hz.
+    pub fn frequency(&self) -> f64 {
+        self.frequency_mhz() * 1.0E6
+    }
+
+    /// Returns the [Constellation]s transmitting this [Carrier].
+    /// [Constellation::SBAS] designates all augmentation systems.
+    pub fn constellations(&self) -> &'static [Constellation] {
+        match self {
+            Self::L1 => &[Constellation::GPS, Constellation::QZSS, Constellation::SBAS],
+            Self::L5 => &[
+                Constellation::GPS,
+                Constellation::QZSS,
+                Constellation::SBAS,
+                Constellation::IRNSS,
+            ],
+            Self::L2 => &[Constellation::GPS, Constellation::QZSS],
+            Self::L6 => &[Constellation::QZSS],
+            Self::E1 | Self::E5a | Self::E5b | Self::E5 | Self::E6 => &[Constellation::Galileo],
+            Self::B1I | Self::B1C | Self::B2a | Self::B2b | Self::B3I => &[Constellation::BeiDou],
+            Self::G1 | Self::G2 | Self::G3 => &[Constellation::Glonass],
+            Self::S => &[Constellation::IRNSS],
+        }
+    }
+
+    /// Returns true if this [Carrier] is transmitted by given [Constellation].
+    pub fn is_transmitted_by(&self, constellation: &Constellation) -> bool {
+        self.constellations()
+            .iter()
+            .any(|c| c == constellation || (*c == Constellation::SBAS && constellation.is_sbas()))
+    }
+
+    /// Returns true if this [Carrier] shares its frequency with given [Carrier]
+    pub fn shares_frequency(&self, rhs: &Self) -> bool {
+        self.frequency_mhz() == rhs.frequency_mhz()
+    }
+}
+
+impl std::str::FromStr for Carrier {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        Self::ALL
+            .iter()
+            .find(|carrier| carrier.to_string().eq_ignore_ascii_case(c))
+            .copied()
+            .ok_or(Error::UnknownCarrier(c.to_string()))
+    }
+}
+
+impl std::fmt::Display for Carrier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn carrier_parsing() {
+        for carrier in Carrier::ALL {
+            assert_eq!(Carrier::from_str(&carrier.to_string()).unwrap(), carrier);
+        }
+        assert_eq!(Carrier::from_str(" e5A").unwrap(), Carrier::E5a);
+        assert!(Carrier::from_str("L3").is_err());
+    }
+
+    #[test]
+    fn carrier_mapping() {
+        assert!(Carrier::L5.shares_frequency(&Carrier::E5a));
+        assert!(Carrier::E5a.shares_frequency(&Carrier::B2a));
+        assert!(!Carrier::B1I.shares_frequency(&Carrier::B1C));
+
+        assert!(Carrier::L1.is_transmitted_by(&Constellation::EGNOS));
+        assert!(Carrier::L5.is_transmitted_by(&Constellation::IRNSS));
+        assert!(!Carrier::L2.is_transmitted_by(&Constellation::Galileo));
+        assert!(Carrier::B2a.is_transmitted_by(&Constellation::BeiDou));
+    }
+}
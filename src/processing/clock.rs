@@ -0,0 +1,163 @@
+//! Clock quantities, to mask clock states (bias, drift and drift rate)
+use std::str::FromStr;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Clock quantities parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown clock quantity \"{0}\"")]
+    UnknownQuantity(String),
+    #[error("invalid {0} value \"{1}\"")]
+    InvalidValue(ClockQuantity, String),
+}
+
+/// [ClockQuantity] designates a clock state, described by its mask target:
+/// `clk` (or `clock`) for the clock offset, `clkdrift` and `clkdriftrate`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ClockQuantity {
+    /// Clock offset (bias), in seconds
+    Offset,
+    /// Clock drift, in s/s
+    Drift,
+    /// Clock drift rate, in s/s²
+    DriftRate,
+}
+
+impl ClockQuantity {
+    /// All known [ClockQuantity]s
+    pub const ALL: [Self; 3] = [Self::Offset, Self::Drift, Self::DriftRate];
+
+    /// Returns the mask target of this [ClockQuantity]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Offset => "clk",
+            Self::Drift => "clkdrift",
+            Self::DriftRate => "clkdriftrate",
+        }
+    }
+
+    /// Returns the SI unit of this [ClockQuantity]
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Offset => "s",
+            Self::Drift => "s/s",
+            Self::DriftRate => "s/s^2",
+        }
+    }
+}
+
+impl FromStr for ClockQuantity {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        if c.eq_ignore_ascii_case("clock") {
+            return Ok(Self::Offset);
+        }
+        Self::ALL
+            .iter()
+            .find(|quantity| quantity.name().eq_ignore_ascii_case(c))
+            .copied()
+            .ok_or(Error::UnknownQuantity(c.to_string()))
+    }
+}
+
+impl std::fmt::Display for ClockQuantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Time units that may describe a clock offset, and their scaling to seconds
+const OFFSET_UNITS: [(&str, f64); 5] = [
+    ("ms", 1.0E-3),
+    ("us", 1.0E-6),
+    ("ns", 1.0E-9),
+    ("ps", 1.0E-12),
+    ("s", 1.0),
+];
+
+/// [ClockValue] is the value of a [ClockQuantity], expressed in its SI unit,
+/// that may be compared directly to the clock states.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockValue {
+    /// [ClockQuantity] this value applies to
+    pub quantity: ClockQuantity,
+    /// Value, expressed in the [ClockQuantity] unit
+    pub value: f64,
+}
+
+impl ClockValue {
+    /// Parses the value of given [ClockQuantity]. Clock offsets may be
+    /// expressed in `s`, `ms`, `us`, `ns` or `ps` (seconds when omitted),
+    /// while drifts may only be suffixed by their SI unit.
+    pub fn parse(quantity: ClockQuantity, content: &str) -> Result<Self, Error> {
+        let c = content.trim();
+        let invalid = || Error::InvalidValue(quantity, c.to_string());
+
+        let (value, scaling) = match quantity {
+            ClockQuantity::Offset => OFFSET_UNITS
+                .iter()
+                .find_map(|(unit, scaling)| Some((c.strip_suffix(unit)?, *scaling)))
+                .unwrap_or((c, 1.0)),
+            quantity => (c.strip_suffix(quantity.unit()).unwrap_or(c), 1.0),
+        };
+
+        let value = f64::from_str(value.trim()).map_err(|_| invalid())?;
+        Ok(Self {
+            quantity,
+            value: value * scaling,
+        })
+    }
+}
+
+impl std::fmt::Display for ClockValue {
+    /// Formats the value only (in SI unit), the [ClockQuantity] being the mask target
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:e}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clock_values() {
+        for quantity in ClockQuantity::ALL {
+            assert_eq!(ClockQuantity::from_str(quantity.name()).unwrap(), quantity);
+        }
+        assert_eq!(
+            ClockQuantity::from_str("Clock").unwrap(),
+            ClockQuantity::Offset
+        );
+
+        for (quantity, desc, expected) in [
+            (ClockQuantity::Offset, "1ms", 1.0E-3),
+            (ClockQuantity::Offset, "-250 ns", -250.0E-9),
+            (ClockQuantity::Offset, "1E-4", 1.0E-4),
+            (ClockQuantity::Offset, "2 s", 2.0),
+            (ClockQuantity::Drift, "1e-9", 1.0E-9),
+            (ClockQuantity::Drift, "1e-9 s/s", 1.0E-9),
+            (ClockQuantity::DriftRate, "-3E-15 s/s^2", -3.0E-15),
+        ] {
+            let clock = ClockValue::parse(quantity, desc).unwrap();
+            assert!(
+                (clock.value - expected).abs() < 1.0E-20,
+                "parsed {} from \"{}\"",
+                clock.value,
+                desc
+            );
+            let display = ClockValue::parse(quantity, &clock.to_string()).unwrap();
+            assert_eq!(display, clock);
+        }
+
+        assert!(ClockValue::parse(ClockQuantity::Offset, "1 h").is_err());
+        assert!(ClockValue::parse(ClockQuantity::Drift, "1 ms").is_err());
+    }
+}
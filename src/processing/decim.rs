@@ -2,6 +2,9 @@ use crate::processing::{FilterItem, ItemError};
 use hifitime::Duration;
 use thiserror::Error;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Decimation filter parsing error
 #[derive(Error, Debug)]
 pub enum Error {
@@ -13,20 +16,28 @@ pub enum Error {
 
 /// Type of decimation filter
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum DecimationFilterType {
     /// Simple modulo decimation
     Modulo(u32),
     /// Duration decimation
+    #[cfg_attr(feature = "serde", serde(with = "crate::processing::schema::duration"))]
     Duration(Duration),
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DecimationFilter {
     /// Type of decimation filter
     pub filter: DecimationFilterType,
     /// Optional decimated item.
     /// When item is None, all data is to be decimated.
     /// When item is specified, only that subset is to be decimated.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub item: Option<FilterItem>,
 }
 
@@ -61,33 +72,48 @@ pub trait Decimate {
     fn decimate_mut(&mut self, f: &DecimationFilter);
 }
 
+impl std::fmt::Display for DecimationFilterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Modulo(r) => write!(f, "{}", r),
+            Self::Duration(dt) => write!(f, "{}", dt),
+        }
+    }
+}
+
+impl std::fmt::Display for DecimationFilter {
+    /// Formats this [DecimationFilter] so it may be parsed back with [DecimationFilter::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.filter)?;
+        if let Some(item) = &self.item {
+            write!(f, ":{}", item)?;
+        }
+        Ok(())
+    }
+}
+
 impl std::str::FromStr for DecimationFilter {
     type Err = Error;
     fn from_str(content: &str) -> Result<Self, Self::Err> {
         let items: Vec<&str> = content.trim().split(':').collect();
-        if let Ok(dt) = Duration::from_str(items[0].trim()) {
+
+        let item = if items.len() > 1 {
+            Some(FilterItem::from_str(items[1].trim())?)
+        } else {
+            None // no subset description
+        };
+
+        // Modulo is tested first: unitless numbers would
+        // otherwise be interpreted as a null Duration
+        if let Ok(r) = items[0].trim().parse::<u32>() {
             Ok(Self {
-                item: {
-                    if items.len() > 1 {
-                        let item = FilterItem::from_str(items[1].trim())?;
-                        Some(item)
-                    } else {
-                        None // no subset description
-                    }
-                },
-                filter: DecimationFilterType::Duration(dt),
+                item,
+                filter: DecimationFilterType::Modulo(r),
             })
-        } else if let Ok(r) = items[0].trim().parse::<u32>() {
+        } else if let Ok(dt) = Duration::from_str(items[0].trim()) {
             Ok(Self {
-                item: {
-                    if items.len() > 1 {
-                        let item = FilterItem::from_str(items[1].trim())?;
-                        Some(item)
-                    } else {
-                        None
-                    }
-                },
-                filter: DecimationFilterType::Modulo(r),
+                item,
+                filter: DecimationFilterType::Duration(dt),
             })
         } else {
             Err(Error::AttributeParsingError(items[0].to_string()))
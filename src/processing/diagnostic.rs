@@ -0,0 +1,280 @@
+//! Span-aware parsing diagnostics, for human friendly error reports.
+use std::ops::Range;
+
+/// What the parser expected to find at a given location
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expectation {
+    /// A mask operand: `>`, `>=`, `<`, `<=`, `=`, `!=`, `in` or `!in`
+    Operand,
+    /// A mask target, that is described prior the operand, like `e` or `snr`
+    Target,
+    /// A filter item, of any kind
+    Item,
+    /// A spacecraft, like `G08`
+    SV,
+    /// A constellation, like `GPS`
+    Constellation,
+    /// A floating point number
+    Float,
+    /// A positive integer number
+    Integer,
+    /// A positive integer number within inclusive range
+    IntegerRange(u8, u8),
+    /// A floating point number within inclusive range
+    FloatRange(f64, f64),
+    /// An interval, like `10..60` or `[30,45)`
+    Interval,
+    /// A carrier signal, like `L1` or `E5a`
+    Carrier,
+    /// An observable, like `L1C` or `C1`
+    Observable,
+    /// A frequency, like `1500MHz`
+    Frequency,
+    /// A time of day, like `22:00 UTC`
+    TimeOfDay,
+    /// A list of days of week, like `sat,sun`
+    DaysOfWeek,
+    /// A week number, like `2310`
+    Week,
+    /// A day of year, like `045` or `2024-045`
+    DayOfYear,
+    /// A time of week, like `86400`
+    TimeOfWeek,
+    /// A Modified Julian Date, like `60000`
+    MJD,
+    /// A geographic region, like `circle(48.8,2.3,5km)`
+    Region,
+    /// An epoch relative to the dataset, like `start+30min` or `last:2h`
+    RelativeEpoch,
+    /// A SNR threshold template, like `template(0:25,15:30,30:35)`
+    SNRTemplate,
+    /// A station marker name, like `ABMF`
+    Marker,
+    /// A DOMES site identification number, like `10003M009`
+    DOMES,
+    /// A COSPAR launch identifier, like `1998-017A`
+    COSPAR,
+    /// An SBAS service, like `EGNOS`
+    SBAS,
+    /// An epoch flag, like `ok` or `powerfailure`
+    EpochFlag,
+    /// A clock value, like `1 ms` or `1e-9`
+    Clock,
+    /// An orbit class, like `GEO` or `MEO`
+    OrbitClass,
+    /// A satellite block (or generation), like `IIIA` or `FOC`
+    Block,
+    /// A set of satellites within valid PRN bounds, like `G01-G10`, `E*` or `!R12`
+    SvSet,
+    /// A list token of the same kind as the other list tokens
+    Homogeneous(&'static str),
+}
+
+impl std::fmt::Display for Expectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Operand => write!(f, "mask operand (>, >=, <, <=, =, !=, in, !in)"),
+            Self::Target => write!(f, "mask target"),
+            Self::Item => write!(f, "filter item"),
+            Self::SV => write!(f, "satellite (like G08)"),
+            Self::Constellation => write!(f, "constellation (like GPS)"),
+            Self::Float => write!(f, "number"),
+            Self::Integer => write!(f, "positive integer"),
+            Self::IntegerRange(min, max) => write!(f, "integer within [{}, {}]", min, max),
+            Self::FloatRange(min, max) => write!(f, "number within [{}, {}]", min, max),
+            Self::Interval => write!(f, "interval (like 10..60 or [30,45))"),
+            Self::Carrier => write!(f, "carrier (like L1 or E5a)"),
+            Self::Observable => write!(f, "observable (like L1C or C1)"),
+            Self::Frequency => write!(f, "frequency (like 1500MHz)"),
+            Self::TimeOfDay => write!(f, "time of day (like 22:00 UTC)"),
+            Self::DaysOfWeek => write!(f, "days of week (like sat,sun)"),
+            Self::Week => write!(f, "week number (like 2310)"),
+            Self::DayOfYear => write!(f, "day of year (like 045 or 2024-045)"),
+            Self::TimeOfWeek => write!(f, "time of week (like 86400)"),
+            Self::MJD => write!(f, "modified julian date (like 60000)"),
+            Self::Region => write!(f, "region (like circle(48.8,2.3,5km) or box(40,-5,50,10))"),
+            Self::RelativeEpoch => {
+                write!(f, "relative epoch (like start+30min, end-2h or last:2h)")
+            }
+            Self::SNRTemplate => write!(f, "snr template (like template(0:25,15:30,30:35))"),
+            Self::Marker => write!(f, "marker name (like ABMF)"),
+            Self::DOMES => write!(f, "DOMES number (like 10003M009)"),
+            Self::COSPAR => write!(f, "COSPAR identifier (like 1998-017A)"),
+            Self::SBAS => write!(f, "SBAS service (like EGNOS or WAAS)"),
+            Self::EpochFlag => write!(f, "epoch flag (like ok or powerfailure)"),
+            Self::Clock => write!(f, "clock value (like 1 ms or 1e-9)"),
+            Self::OrbitClass => write!(f, "orbit class (GEO, IGSO, MEO or LEO)"),
+            Self::Block => write!(f, "satellite block (like IIIA or FOC)"),
+            Self::SvSet => write!(f, "satellites within PRN bounds (like G01-G10, E* or !R12)"),
+            Self::Homogeneous(kind) => write!(f, "{}, like the other list items", kind),
+        }
+    }
+}
+
+/// [Diagnostic] describes a parsing error within a filter description,
+/// with the byte span of the faulty token. Use [Diagnostic::render]
+/// to underline the faulty token within the original description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Byte span of the faulty token, within the description
+    pub span: Range<usize>,
+    /// What the parser expected at this location
+    pub expected: Expectation,
+    /// What was actually found
+    pub found: String,
+    /// Possible correction
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.found.is_empty() {
+            write!(f, "expected {}", self.expected)?;
+        } else {
+            write!(f, "expected {}, found \"{}\"", self.expected, self.found)?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ": did you mean \"{}\"?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl Diagnostic {
+    /// Builds a new [Diagnostic] for given token, starting at given offset.
+    pub(crate) fn new(offset: usize, found: &str, expected: Expectation) -> Self {
+        Self {
+            span: offset..offset + found.len(),
+            expected,
+            found: found.to_string(),
+            suggestion: None,
+        }
+    }
+
+    /// Proposes the closest candidate, if any is close enough to the faulty token.
+    pub(crate) fn with_suggestion(mut self, candidates: &[&str]) -> Self {
+        self.suggestion = suggest(&self.found, candidates).map(|s| s.to_string());
+        self
+    }
+
+    /// Shifts this [Diagnostic] by given amount of bytes, when the faulty
+    /// token was parsed from a portion of a larger description.
+    pub(crate) fn shifted(mut self, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self
+    }
+
+    /// Renders this [Diagnostic] with the faulty token underlined within
+    /// the original description, for example:
+    /// ```text
+    /// GSP & e>10
+    /// ^^^ expected constellation (like GPS), found "GSP": did you mean "GPS"?
+    /// ```
+    pub fn render(&self, description: &str) -> String {
+        let start = description
+            .get(..self.span.start)
+            .map(|s| s.chars().count())
+            .unwrap_or(description.chars().count());
+
+        let width = description
+            .get(self.span.clone())
+            .map(|s| s.chars().count())
+            .unwrap_or(0)
+            .max(1);
+
+        format!(
+            "{}\n{}{} {}",
+            description,
+            " ".repeat(start),
+            "^".repeat(width),
+            self
+        )
+    }
+}
+
+/// Optimal string alignment distance (Levenshtein distance with transpositions),
+/// case insensitive.
+fn distance(lhs: &str, rhs: &str) -> usize {
+    let lhs = lhs.to_ascii_lowercase().chars().collect::<Vec<_>>();
+    let rhs = rhs.to_ascii_lowercase().chars().collect::<Vec<_>>();
+
+    let mut d = vec![vec![0usize; rhs.len() + 1]; lhs.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=lhs.len() {
+        for j in 1..=rhs.len() {
+            let cost = if lhs[i - 1] == rhs[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && lhs[i - 1] == rhs[j - 2] && lhs[i - 2] == rhs[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[lhs.len()][rhs.len()]
+}
+
+/// Returns the closest candidate, if it is close enough to be a likely typo.
+pub(crate) fn suggest<'a>(found: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let threshold = if found.chars().count() > 4 { 2 } else { 1 };
+    candidates
+        .iter()
+        .map(|candidate| (distance(found, candidate), *candidate))
+        .filter(|(d, _)| *d > 0 && *d <= threshold)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Known SBAS services, for suggestion purposes
+pub(crate) const SBAS_SERVICES: &[&str] = &[
+    "WAAS", "EGNOS", "MSAS", "GAGAN", "SDCM", "BDSBAS", "KASS", "SPAN", "ASAL",
+];
+
+/// Constellation names we propose in our suggestions
+pub(crate) const CONSTELLATIONS: &[&str] = &[
+    "GPS", "GLO", "GAL", "BDS", "QZSS", "IRNSS", "SBAS", "Glonass", "Galileo", "BeiDou", "WAAS",
+    "EGNOS", "MSAS", "GAGAN", "SDCM", "BDSBAS", "KASS",
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(distance("GSP", "GPS"), 1);
+        assert_eq!(distance("snt", "SNR"), 1);
+        assert_eq!(suggest("GSP", CONSTELLATIONS), Some("GPS"));
+        assert_eq!(suggest("gall", CONSTELLATIONS), Some("GAL"));
+        assert_eq!(suggest("Gallileo", CONSTELLATIONS), Some("Galileo"));
+        assert_eq!(suggest("iode", CONSTELLATIONS), None);
+        assert_eq!(suggest("GPS", CONSTELLATIONS), None);
+    }
+
+    #[test]
+    fn rendering() {
+        let diag = Diagnostic::new(5, "120", Expectation::FloatRange(0.0, 90.0));
+        assert_eq!(
+            diag.render("e >= 120"),
+            "e >= 120\n     ^^^ expected number within [0, 90], found \"120\""
+        );
+
+        let diag = Diagnostic::new(0, "GSP", Expectation::Constellation)
+            .with_suggestion(CONSTELLATIONS)
+            .shifted(1);
+        assert_eq!(
+            diag.render("=GSP"),
+            "=GSP\n ^^^ expected constellation (like GPS), found \"GSP\": did you mean \"GPS\"?"
+        );
+    }
+}
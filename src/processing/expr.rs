@@ -0,0 +1,529 @@
+//! Boolean combination of [MaskFilter]s
+use crate::processing::{Diagnostic, Interval, MaskError, MaskFilter};
+use hifitime::Epoch;
+use thiserror::Error;
+
+/// [FilterExpr] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("empty filter expression")]
+    EmptyExpression,
+    #[error("unbalanced parenthesis")]
+    UnbalancedParenthesis,
+    #[error("missing operand after \"{0}\"")]
+    MissingOperand(char),
+    #[error("unexpected token \"{0}\"")]
+    UnexpectedToken(String),
+    #[error("invalid mask filter")]
+    MaskFilterParsing(#[from] MaskError),
+}
+
+impl Error {
+    /// Returns the [Diagnostic] attached to this error, if any.
+    /// Its span is expressed within the entire expression.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            Self::MaskFilterParsing(e) => e.diagnostic(),
+            _ => None,
+        }
+    }
+}
+
+/// [FilterExpr] combines several [MaskFilter]s with boolean logic,
+/// so an entire selection policy may be described at once, for example:
+/// `GPS & e>10 | (GAL & snr>35)`.
+///
+/// Supported operators, by decreasing priority:
+/// - `!` negation. Not to be confused with the `!=` and `!in` mask operands.
+/// - `&` logical AND: both masks are applied successively.
+/// - `|` logical OR: both branches are applied separately and then merged together.
+///
+/// Parenthesis may be used to group terms.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    /// Single [MaskFilter] (leaf)
+    Mask(MaskFilter),
+    /// Logical AND of both terms
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    /// Logical OR of both terms
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    /// Logical negation of inner term
+    Not(Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    /// Returns the complement of this [FilterExpr], with negations
+    /// pushed down to the leaves (De Morgan's laws), so it only
+    /// contains AND and OR nodes on top of [MaskFilter]s.
+    pub fn complement(&self) -> Self {
+        match self {
+            Self::Mask(mask) => Self::Mask(mask.complement()),
+            Self::And(lhs, rhs) => Self::Or(Box::new(lhs.complement()), Box::new(rhs.complement())),
+            Self::Or(lhs, rhs) => Self::And(Box::new(lhs.complement()), Box::new(rhs.complement())),
+            Self::Not(inner) => inner.normalized(),
+        }
+    }
+
+    /// Returns this [FilterExpr] with all negations pushed down to the leaves.
+    /// The resulting expression only contains AND and OR nodes.
+    pub fn normalized(&self) -> Self {
+        match self {
+            Self::Mask(_) => self.clone(),
+            Self::And(lhs, rhs) => {
+                Self::And(Box::new(lhs.normalized()), Box::new(rhs.normalized()))
+            }
+            Self::Or(lhs, rhs) => Self::Or(Box::new(lhs.normalized()), Box::new(rhs.normalized())),
+            Self::Not(inner) => inner.complement(),
+        }
+    }
+
+    /// Resolves the relative epochs of all [MaskFilter]s against given time span,
+    /// see [MaskFilter::resolved].
+    pub fn resolved(&self, span: &Interval<Epoch>) -> Self {
+        match self {
+            Self::Mask(mask) => Self::Mask(mask.resolved(span)),
+            Self::And(lhs, rhs) => {
+                Self::And(Box::new(lhs.resolved(span)), Box::new(rhs.resolved(span)))
+            }
+            Self::Or(lhs, rhs) => {
+                Self::Or(Box::new(lhs.resolved(span)), Box::new(rhs.resolved(span)))
+            }
+            Self::Not(inner) => Self::Not(Box::new(inner.resolved(span))),
+        }
+    }
+
+    /// Returns all [MaskFilter]s contained in this [FilterExpr]
+    pub fn masks(&self) -> Vec<&MaskFilter> {
+        match self {
+            Self::Mask(mask) => vec![mask],
+            Self::And(lhs, rhs) | Self::Or(lhs, rhs) => {
+                let mut masks = lhs.masks();
+                masks.extend(rhs.masks());
+                masks
+            }
+            Self::Not(inner) => inner.masks(),
+        }
+    }
+}
+
+impl From<MaskFilter> for FilterExpr {
+    fn from(mask: MaskFilter) -> Self {
+        Self::Mask(mask)
+    }
+}
+
+impl std::ops::Not for FilterExpr {
+    type Output = Self;
+    fn not(self) -> Self {
+        match self {
+            Self::Not(inner) => *inner,
+            _ => Self::Not(Box::new(self)),
+        }
+    }
+}
+
+impl std::ops::BitAnd for FilterExpr {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::BitOr for FilterExpr {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::BitOrAssign for FilterExpr {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.clone() | rhs;
+    }
+}
+
+impl std::fmt::Display for FilterExpr {
+    /// Formats this [FilterExpr] so it may be parsed back with [FilterExpr::from_str].
+    /// Nested and negated terms are always enclosed in parenthesis.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fn term(expr: &FilterExpr, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match expr {
+                FilterExpr::Mask(mask) => write!(f, "{}", mask),
+                _ => write!(f, "({})", expr),
+            }
+        }
+        match self {
+            Self::Mask(mask) => write!(f, "{}", mask),
+            Self::And(lhs, rhs) => {
+                term(lhs, f)?;
+                write!(f, " & ")?;
+                term(rhs, f)
+            }
+            Self::Or(lhs, rhs) => {
+                term(lhs, f)?;
+                write!(f, " | ")?;
+                term(rhs, f)
+            }
+            // always enclosed, "!=" would otherwise be confused with the mask operand
+            Self::Not(inner) => write!(f, "!({})", inner),
+        }
+    }
+}
+
+/// Expression tokens, with byte offset of the leaves
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Leaf(usize, &'a str),
+}
+
+fn tokenize(content: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut leaf_start: Option<usize> = None;
+    let mut depth = 0;
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        let token = match c {
+            // interval bounds and parameters belong to the leaf,
+            // like "snr in [30,45)" or "region=circle(48.8,2.3,5km)"
+            '(' | '[' if depth > 0 => {
+                depth += 1;
+                None
+            }
+            ')' | ']' if depth > 0 => {
+                depth -= 1;
+                None
+            }
+            _ if depth > 0 => None,
+            '[' => {
+                depth = 1;
+                None
+            }
+            '(' if leaf_start.is_some_and(|start| opens_parameters(&content[start..offset])) => {
+                depth = 1;
+                None
+            }
+            '&' => Some(Token::And),
+            '|' => Some(Token::Or),
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            // "!=" and "!in" are mask operands, not negations,
+            // while "!" following a list separator is an exclusion, like "R01-R24,!R12"
+            '!' if !matches!(chars.peek(), Some((_, '=')))
+                && !is_outside_operand(&content[offset..])
+                && !content[..offset].trim_end().ends_with(',') =>
+            {
+                Some(Token::Not)
+            }
+            _ => None,
+        };
+
+        match token {
+            Some(token) => {
+                if let Some(start) = leaf_start.take() {
+                    push_leaf(&mut tokens, content, start, offset);
+                }
+                tokens.push(token);
+            }
+            None => {
+                if leaf_start.is_none() {
+                    leaf_start = Some(offset);
+                }
+            }
+        }
+    }
+
+    if let Some(start) = leaf_start {
+        push_leaf(&mut tokens, content, start, content.len());
+    }
+
+    tokens
+}
+
+/// Returns true if content starts with the "!in" mask operand
+fn is_outside_operand(content: &str) -> bool {
+    content
+        .get(..3)
+        .is_some_and(|start| start.eq_ignore_ascii_case("!in"))
+        && !content[3..].starts_with(|c: char| c.is_alphanumeric())
+}
+
+/// Returns true if a leaf ends with a range mask operand, or an identifier
+/// (like `circle`), so the following parenthesis opens an interval or parameters,
+/// rather than a group
+fn opens_parameters(leaf: &str) -> bool {
+    leaf.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+        || leaf
+            .split_whitespace()
+            .last()
+            .is_some_and(|word| word.eq_ignore_ascii_case("in") || word.eq_ignore_ascii_case("!in"))
+}
+
+fn push_leaf<'a>(tokens: &mut Vec<Token<'a>>, content: &'a str, start: usize, end: usize) {
+    let leaf = &content[start..end];
+    let trimmed = leaf.trim_start();
+    let start = start + leaf.len() - trimmed.len();
+    let trimmed = trimmed.trim_end();
+    if !trimmed.is_empty() {
+        tokens.push(Token::Leaf(start, trimmed));
+    }
+}
+
+/// Recursive descent parser, respecting operators priority.
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // or := and ('|' and)*
+    fn parse_or(&mut self) -> Result<FilterExpr, Error> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            let rhs = self.parse_and().map_err(|e| match e {
+                Error::EmptyExpression => Error::MissingOperand('|'),
+                e => e,
+            })?;
+            lhs |= rhs;
+        }
+        Ok(lhs)
+    }
+
+    // and := unary ('&' unary)*
+    fn parse_and(&mut self) -> Result<FilterExpr, Error> {
+        let mut lhs = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            let rhs = self.parse_unary().map_err(|e| match e {
+                Error::EmptyExpression => Error::MissingOperand('&'),
+                e => e,
+            })?;
+            lhs = lhs & rhs;
+        }
+        Ok(lhs)
+    }
+
+    // unary := '!' unary | '(' or ')' | leaf
+    fn parse_unary(&mut self) -> Result<FilterExpr, Error> {
+        match self.next() {
+            Some(Token::Not) => {
+                let inner = self.parse_unary().map_err(|e| match e {
+                    Error::EmptyExpression => Error::MissingOperand('!'),
+                    e => e,
+                })?;
+                Ok(FilterExpr::Not(Box::new(inner)))
+            }
+            Some(Token::Open) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err(Error::UnbalancedParenthesis),
+                }
+            }
+            Some(Token::Leaf(offset, leaf)) => Ok(FilterExpr::Mask(
+                leaf.parse::<MaskFilter>().map_err(|e| e.shifted(offset))?,
+            )),
+            Some(Token::Close) => Err(Error::UnbalancedParenthesis),
+            Some(Token::And) => Err(Error::UnexpectedToken("&".to_string())),
+            Some(Token::Or) => Err(Error::UnexpectedToken("|".to_string())),
+            None => Err(Error::EmptyExpression),
+        }
+    }
+}
+
+impl std::str::FromStr for FilterExpr {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(content),
+            pos: 0,
+        };
+
+        let expr = parser.parse_or()?;
+
+        match parser.next() {
+            None => Ok(expr),
+            Some(Token::Close) => Err(Error::UnbalancedParenthesis),
+            Some(Token::Leaf(_, leaf)) => Err(Error::UnexpectedToken(leaf.to_string())),
+            Some(token) => Err(Error::UnexpectedToken(format!("{:?}", token))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn mask(desc: &str) -> Box<FilterExpr> {
+        Box::new(FilterExpr::Mask(MaskFilter::from_str(desc).unwrap()))
+    }
+
+    #[test]
+    fn expr_parsing() {
+        let expr = FilterExpr::from_str("GPS & e>10 | (GAL & snr>35)").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Or(
+                Box::new(FilterExpr::And(mask("GPS"), mask("e>10"))),
+                Box::new(FilterExpr::And(mask("GAL"), mask("snr>35"))),
+            )
+        );
+
+        let expr = FilterExpr::from_str("!=GPS & !(G08, G09)").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(mask("!=GPS"), Box::new(FilterExpr::Not(mask("G08, G09"))),)
+        );
+
+        let expr = FilterExpr::from_str("(e in (10, 60] | snr in [30,45)) & e !in 20..30").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(
+                Box::new(FilterExpr::Or(
+                    mask("e in (10, 60]"),
+                    mask("snr in [30,45)")
+                )),
+                mask("e !in 20..30"),
+            )
+        );
+
+        let expr =
+            FilterExpr::from_str("region=circle(48.8,2.3,5km) | !(region in box(40,-5,50,10))")
+                .unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Or(
+                mask("region=circle(48.8,2.3,5km)"),
+                Box::new(FilterExpr::Not(mask("region in box(40,-5,50,10)"))),
+            )
+        );
+
+        let expr = FilterExpr::from_str("(region = altitude([0,500)) & GPS)").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(mask("region = altitude([0,500))"), mask("GPS"))
+        );
+
+        let expr =
+            FilterExpr::from_str("snr>template(0:25,15:30) & !(GPS & snr<table(L5,0:20))").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(
+                mask("snr>template(0:25,15:30)"),
+                Box::new(FilterExpr::Not(Box::new(FilterExpr::And(
+                    mask("GPS"),
+                    mask("snr<table(L5,0:20)")
+                )))),
+            )
+        );
+
+        let expr = FilterExpr::from_str("R01-R24,!R12 & !(sv=C*, !C01-C05 | E01)").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(
+                mask("R01-R24,!R12"),
+                Box::new(FilterExpr::Not(Box::new(FilterExpr::Or(
+                    mask("sv=C*, !C01-C05"),
+                    mask("E01")
+                )))),
+            )
+        );
+        assert!(FilterExpr::from_str("GPS, !G01 & e>10").is_ok());
+
+        let expr = FilterExpr::from_str(" >2020-01-14T00:31:55 UTC ").unwrap();
+        assert_eq!(*mask(">2020-01-14T00:31:55 UTC"), expr);
+
+        for desc in ["", "GPS &", "(GPS | GAL", "GPS)", "| GAL", "GPS & !"] {
+            assert!(
+                FilterExpr::from_str(desc).is_err(),
+                "parsed invalid expression \"{}\"",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    fn expr_diagnostics() {
+        let desc = "GSP & e>10";
+        let err = FilterExpr::from_str(desc).unwrap_err();
+        let diagnostic = err.diagnostic().unwrap();
+        assert_eq!(diagnostic.span, 0..3);
+        assert_eq!(
+            diagnostic.render(desc),
+            "GSP & e>10\n^^^ expected constellation (like GPS), found \"GSP\": did you mean \"GPS\"?"
+        );
+
+        let err = FilterExpr::from_str("GPS & (elev > 95 | snr in (30, 45])").unwrap_err();
+        assert_eq!(err.diagnostic().unwrap().span, 14..16);
+    }
+
+    #[test]
+    fn expr_display() {
+        for desc in [
+            "GPS & e>10 | (GAL & snr>35)",
+            "!(GPS | !GAL) & >2020-01-14T00:31:55 UTC",
+            "!(e in (10, 60]) & snr !in [30,45)",
+            "region=polygon(0,0,0,10,10,10) | (GPS & region in circle(48.8,2.3,5km))",
+        ] {
+            let expr = FilterExpr::from_str(desc).unwrap();
+            let formatted = expr.to_string();
+            assert_eq!(FilterExpr::from_str(&formatted).unwrap(), expr);
+        }
+    }
+
+    #[test]
+    fn expr_normalization() {
+        let expr = FilterExpr::from_str("!(GPS & e>10)").unwrap();
+        assert_eq!(
+            expr.normalized(),
+            FilterExpr::Or(mask("!=GPS"), mask("e<=10")),
+        );
+
+        let expr = FilterExpr::from_str("!(e>=10 | snr in [30,45))").unwrap();
+        assert_eq!(
+            expr.normalized(),
+            FilterExpr::And(mask("e<10"), mask("snr !in [30,45)")),
+        );
+
+        let expr = FilterExpr::from_str("!(GPS | !GAL)").unwrap();
+        assert_eq!(
+            expr.normalized(),
+            FilterExpr::And(mask("!=GPS"), mask("=GAL")),
+        );
+    }
+
+    #[test]
+    fn mask_operators() {
+        let gps = MaskFilter::from_str("GPS").unwrap();
+        let elev = MaskFilter::from_str("e>10").unwrap();
+
+        // operand or item mismatch: nothing is dropped
+        let expr = gps.clone() | elev.clone();
+        assert_eq!(expr, FilterExpr::Or(mask("GPS"), mask("e>10")));
+        assert_eq!(expr, FilterExpr::from_str("GPS | e>10").unwrap());
+
+        let mut expr = FilterExpr::from(gps.clone());
+        expr |= elev.into();
+        assert_eq!(expr, gps.clone() | MaskFilter::from_str("e>10").unwrap());
+
+        let expr = MaskFilter::from_str("!=G08").unwrap() | MaskFilter::from_str("!=G09").unwrap();
+        assert_eq!(expr, FilterExpr::Or(mask("!=G08"), mask("!=G09")));
+    }
+}
@@ -0,0 +1,161 @@
+//! Observation quality flags, following the RINEX definitions
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Observation flags parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown epoch flag \"{0}\"")]
+    UnknownEpochFlag(String),
+    #[error("invalid loss of lock indicator \"{0}\"")]
+    InvalidLLI(String),
+    #[error("invalid signal strength indicator \"{0}\"")]
+    InvalidSSI(String),
+}
+
+/// Highest Loss of Lock Indicator: LLI are 3 bit masks, where
+/// - bit 0 indicates a loss of lock (possible cycle slip)
+/// - bit 1 indicates a half cycle ambiguity
+/// - bit 2 indicates the observation was made under Anti Spoofing in RINEX 2,
+///   and the BOC tracking of an MBOC modulated signal in RINEX 3 and 4.
+///
+/// Observations without LLI are considered as 0 (no flag raised).
+pub const LLI_MAX: u8 = 7;
+
+/// Returns true if this Loss of Lock Indicator raises any of given bits.
+/// LLI masks test bits rather than compare values: `lli=3` retains observations
+/// with either a loss of lock or a half cycle ambiguity. No bits (0)
+/// only match observations without any flag raised.
+pub fn lli_matches(lli: u8, bits: u8) -> bool {
+    if bits == 0 { lli == 0 } else { lli & bits != 0 }
+}
+
+/// Highest Signal Strength Indicator: SSI range from 1 (minimum) to 9 (maximum),
+/// 5 being the threshold for a good signal to noise ratio, 0 meaning unknown.
+/// Observations without SSI are considered as 0 (unknown).
+pub const SSI_MAX: u8 = 9;
+
+/// Parses a Loss of Lock Indicator (0 to [LLI_MAX])
+pub(crate) fn parse_lli(content: &str) -> Result<u8, Error> {
+    let c = content.trim();
+    u8::from_str(c)
+        .ok()
+        .filter(|lli| *lli <= LLI_MAX)
+        .ok_or(Error::InvalidLLI(c.to_string()))
+}
+
+/// Parses a Signal Strength Indicator (0 to [SSI_MAX])
+pub(crate) fn parse_ssi(content: &str) -> Result<u8, Error> {
+    let c = content.trim();
+    u8::from_str(c)
+        .ok()
+        .filter(|ssi| *ssi <= SSI_MAX)
+        .ok_or(Error::InvalidSSI(c.to_string()))
+}
+
+/// [EpochFlag] describes the observation epoch event, as defined in RINEX.
+/// Flags are described by their name (like `powerfailure`), or their RINEX value (like `1`).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EpochFlag {
+    /// Epoch is sane (0)
+    #[default]
+    Ok,
+    /// Power failure occurred since previous epoch (1)
+    PowerFailure,
+    /// Antenna is being moved, starting at this epoch (2)
+    AntennaBeingMoved,
+    /// New site occupation, end of kinematic data (3)
+    NewSiteOccupation,
+    /// Header information follows (4)
+    HeaderInformation,
+    /// External event (5)
+    ExternalEvent,
+    /// Cycle slip records follow (6)
+    CycleSlip,
+}
+
+impl EpochFlag {
+    /// All known [EpochFlag]s, sorted by RINEX value
+    pub const ALL: [Self; 7] = [
+        Self::Ok,
+        Self::PowerFailure,
+        Self::AntennaBeingMoved,
+        Self::NewSiteOccupation,
+        Self::HeaderInformation,
+        Self::ExternalEvent,
+        Self::CycleSlip,
+    ];
+
+    /// Returns the name of this [EpochFlag]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::PowerFailure => "powerfailure",
+            Self::AntennaBeingMoved => "antennabeingmoved",
+            Self::NewSiteOccupation => "newsiteoccupation",
+            Self::HeaderInformation => "headerinformation",
+            Self::ExternalEvent => "externalevent",
+            Self::CycleSlip => "cycleslip",
+        }
+    }
+
+    /// Returns the RINEX value of this [EpochFlag]
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns true if this epoch is sane
+    pub fn is_ok(&self) -> bool {
+        *self == Self::Ok
+    }
+}
+
+impl FromStr for EpochFlag {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        Self::ALL
+            .iter()
+            .find(|flag| flag.name().eq_ignore_ascii_case(c) || flag.value().to_string() == c)
+            .copied()
+            .ok_or(Error::UnknownEpochFlag(c.to_string()))
+    }
+}
+
+impl std::fmt::Display for EpochFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn observation_flags() {
+        for flag in EpochFlag::ALL {
+            assert_eq!(EpochFlag::from_str(flag.name()).unwrap(), flag);
+            assert_eq!(
+                EpochFlag::from_str(&flag.value().to_string()).unwrap(),
+                flag
+            );
+        }
+        assert_eq!(
+            EpochFlag::from_str(" PowerFailure").unwrap(),
+            EpochFlag::PowerFailure
+        );
+        assert!(EpochFlag::from_str("7").is_err());
+        assert!(!EpochFlag::AntennaBeingMoved.is_ok());
+
+        assert_eq!(parse_lli("4").unwrap(), 4);
+        assert!(lli_matches(0, 0));
+        assert!(!lli_matches(1, 0));
+        assert!(lli_matches(5, 1));
+        assert!(lli_matches(2, 3));
+        assert!(!lli_matches(4, 3));
+        assert!(parse_lli("8").is_err());
+        assert_eq!(parse_ssi("9").unwrap(), 9);
+        assert!(parse_ssi("-1").is_err());
+    }
+}
@@ -0,0 +1,447 @@
+use crate::processing::{FilterItem, Interval, ItemError};
+use hifitime::{Duration, Epoch};
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Interpolation filter parsing error
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown interpolation method \"{0}\"")]
+    UnknownMethod(String),
+    #[error("invalid interpolation order \"{0}\"")]
+    InvalidOrder(String),
+    #[error("invalid interpolation series \"{0}\"")]
+    InvalidSeries(String),
+    #[error("invalid interpolated item")]
+    InvalidItem(#[from] ItemError),
+}
+
+/// Maximal order of [InterpMethod::Lagrange] interpolations: higher orders
+/// are numerically unstable on evenly spaced samples.
+const LAGRANGE_MAX_ORDER: usize = 20;
+
+/// Interpolation methods
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum InterpMethod {
+    /// Linear interpolation between the two surrounding samples
+    Linear,
+    /// Lagrange polynomial interpolation of given order (up to 20),
+    /// using the (order +1) samples surrounding the interpolated point
+    Lagrange(usize),
+    /// Cubic Hermite spline interpolation, with finite difference tangents
+    CubicHermite,
+}
+
+impl InterpMethod {
+    /// Interpolates y(x) from (x, y) samples sorted by increasing x.
+    /// Returns None when x is outside the sampled range (no extrapolation),
+    /// or when there are not enough samples for this method.
+    pub fn interpolate(&self, samples: &[(f64, f64)], x: f64) -> Option<f64> {
+        let (first, last) = (samples.first()?, samples.last()?);
+        if !x.is_finite() || x < first.0 || x > last.0 {
+            return None;
+        }
+
+        // index of the first sample that is not lower than x
+        let idx = samples.partition_point(|(xi, _)| *xi < x);
+        if samples[idx].0 == x {
+            return Some(samples[idx].1);
+        }
+
+        match self {
+            Self::Linear => {
+                let ((x0, y0), (x1, y1)) = (samples[idx - 1], samples[idx]);
+                Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+            }
+            Self::Lagrange(order) => {
+                let n = order.checked_add(1)?;
+                if samples.len() < n {
+                    return None;
+                }
+                let start = idx.saturating_sub(n / 2).min(samples.len() - n);
+                Some(lagrange(&samples[start..start + n], x))
+            }
+            Self::CubicHermite => {
+                let (i0, i1) = (idx - 1, idx);
+                let ((x0, y0), (x1, y1)) = (samples[i0], samples[i1]);
+                let (m0, m1) = (tangent(samples, i0), tangent(samples, i1));
+
+                let h = x1 - x0;
+                let t = (x - x0) / h;
+                let (t2, t3) = (t * t, t * t * t);
+
+                let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+                let h10 = t3 - 2.0 * t2 + t;
+                let h01 = -2.0 * t3 + 3.0 * t2;
+                let h11 = t3 - t2;
+
+                Some(h00 * y0 + h10 * h * m0 + h01 * y1 + h11 * h * m1)
+            }
+        }
+    }
+
+    /// Interpolates y(t) from (t, y) samples sorted chronologically.
+    /// See [Self::interpolate] for more information.
+    pub fn interpolate_epoch(&self, samples: &[(Epoch, f64)], t: Epoch) -> Option<f64> {
+        let t0 = samples.first()?.0;
+        let samples = samples
+            .iter()
+            .map(|(ti, yi)| ((*ti - t0).to_seconds(), *yi))
+            .collect::<Vec<_>>();
+        self.interpolate(&samples, (t - t0).to_seconds())
+    }
+}
+
+/// Lagrange polynomial evaluated at x
+fn lagrange(samples: &[(f64, f64)], x: f64) -> f64 {
+    let mut y = 0.0;
+    for (j, (xj, yj)) in samples.iter().enumerate() {
+        let mut l = 1.0;
+        for (m, (xm, _)) in samples.iter().enumerate() {
+            if m != j {
+                l *= (x - xm) / (xj - xm);
+            }
+        }
+        y += yj * l;
+    }
+    y
+}
+
+/// Finite difference tangent at i-th sample
+fn tangent(samples: &[(f64, f64)], i: usize) -> f64 {
+    let prev = samples[i.saturating_sub(1)];
+    let next = samples[(i + 1).min(samples.len() - 1)];
+    (next.1 - prev.1) / (next.0 - prev.0)
+}
+
+impl std::str::FromStr for InterpMethod {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim().to_ascii_lowercase();
+        if c.eq("linear") {
+            Ok(Self::Linear)
+        } else if c.eq("hermite") {
+            Ok(Self::CubicHermite)
+        } else if let Some(order) = c.strip_prefix("lagrange") {
+            let order = order.trim();
+            let order = order
+                .strip_prefix('(')
+                .and_then(|order| order.strip_suffix(')'))
+                .and_then(|order| order.trim().parse::<usize>().ok())
+                .filter(|order| (1..=LAGRANGE_MAX_ORDER).contains(order))
+                .ok_or(Error::InvalidOrder(order.to_string()))?;
+            Ok(Self::Lagrange(order))
+        } else {
+            Err(Error::UnknownMethod(c))
+        }
+    }
+}
+
+impl std::fmt::Display for InterpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Linear => write!(f, "linear"),
+            Self::Lagrange(order) => write!(f, "lagrange({})", order),
+            Self::CubicHermite => write!(f, "hermite"),
+        }
+    }
+}
+
+/// Series of [Epoch]s we interpolate to
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum InterpSeries {
+    /// List of [Epoch]s, described as `t0,t1,..`
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::display_fromstr_vec")
+    )]
+    Epochs(Vec<Epoch>),
+    /// Evenly spaced [Epoch]s within [Interval], described as `interval/period`
+    Interval {
+        /// [Epoch] [Interval]
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::processing::schema::display_fromstr")
+        )]
+        interval: Interval<Epoch>,
+        /// Sampling period
+        #[cfg_attr(feature = "serde", serde(with = "crate::processing::schema::duration"))]
+        period: Duration,
+    },
+}
+
+impl InterpSeries {
+    /// Returns the [Epoch]s of this [InterpSeries].
+    /// Intervals with a null or negative period do not contain any [Epoch].
+    pub fn epochs(&self) -> Vec<Epoch> {
+        match self {
+            Self::Epochs(epochs) => epochs.clone(),
+            Self::Interval { period, .. } if *period <= Duration::ZERO => Vec::new(),
+            Self::Interval { interval, period } => {
+                let mut epochs = Vec::new();
+                let mut t = interval.start;
+                if !interval.start_inclusive {
+                    t += *period;
+                }
+                while interval.contains(&t) {
+                    epochs.push(t);
+                    t += *period;
+                }
+                epochs
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for InterpSeries {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let invalid = || Error::InvalidSeries(c.to_string());
+        if let Some((interval, period)) = c.rsplit_once('/') {
+            let interval = Interval::<Epoch>::from_str(interval).map_err(|_| invalid())?;
+            let period = Duration::from_str(period.trim()).map_err(|_| invalid())?;
+            if period <= Duration::ZERO {
+                return Err(invalid());
+            }
+            Ok(Self::Interval { interval, period })
+        } else {
+            let epochs = c
+                .split(',')
+                .map(|epoch| Epoch::from_str(epoch.trim()).map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Self::Epochs(epochs))
+        }
+    }
+}
+
+impl std::fmt::Display for InterpSeries {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Epochs(epochs) => {
+                let epochs = epochs.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", epochs.join(","))
+            }
+            Self::Interval { interval, period } => write!(f, "{}/{}", interval, period),
+        }
+    }
+}
+
+/// Interpolation filter, to resample data subsets to a new [InterpSeries].
+/// It is described as `method:series[:item]`, for example
+/// `lagrange(7):2020-01-01T00:00:00 GPST..2020-01-02T00:00:00 GPST/30 s:C1C`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InterpFilter {
+    /// Interpolation method
+    pub method: InterpMethod,
+    /// [Epoch]s to interpolate to
+    pub series: InterpSeries,
+    /// Possible targeted subset to narrow down filter's application.
+    /// When undefined, the filter applies to entire dataset
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub item: Option<FilterItem>,
+}
+
+impl InterpFilter {
+    /// Builds a new [InterpFilter]
+    pub fn new(method: InterpMethod, series: InterpSeries) -> Self {
+        Self {
+            method,
+            series,
+            item: None,
+        }
+    }
+    /// Adds targetted item to be interpolated
+    pub fn with_item(&self, item: FilterItem) -> Self {
+        let mut s = self.clone();
+        s.item = Some(item.clone());
+        s
+    }
+}
+
+impl std::str::FromStr for InterpFilter {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let (method, series) = c
+            .split_once(':')
+            .ok_or(Error::InvalidSeries(c.to_string()))?;
+
+        let method = InterpMethod::from_str(method)?;
+
+        // Epochs contain ':' themselves: the item is only
+        // split apart when the entire description is not a valid series
+        if let Ok(series) = InterpSeries::from_str(series) {
+            return Ok(Self::new(method, series));
+        }
+
+        match series.rsplit_once(':') {
+            Some((series, item)) => Ok(Self {
+                method,
+                series: InterpSeries::from_str(series)?,
+                item: Some(FilterItem::from_str(item.trim())?),
+            }),
+            None => Err(Error::InvalidSeries(series.trim().to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for InterpFilter {
+    /// Formats this [InterpFilter] so it may be parsed back with [InterpFilter::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.method, self.series)?;
+        if let Some(item) = &self.item {
+            write!(f, ":{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+/// The [Interpolate] trait is implemented to resample data subsets.
+pub trait Interpolate {
+    /// Immutable interpolation
+    fn interpolate(&self, f: &InterpFilter) -> Self;
+    /// Mutable interpolation
+    fn interpolate_mut(&mut self, f: &InterpFilter);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::processing::Observable;
+    use std::str::FromStr;
+
+    #[test]
+    fn interp_methods() {
+        // y = x^3 - 2x
+        let samples = (0..10)
+            .map(|i| {
+                let x = i as f64;
+                (x, x.powi(3) - 2.0 * x)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(InterpMethod::Linear.interpolate(&samples, 2.0), Some(4.0));
+        assert_eq!(InterpMethod::Linear.interpolate(&samples, 1.5), Some(1.5));
+        assert_eq!(InterpMethod::Linear.interpolate(&samples, 9.5), None);
+        assert_eq!(InterpMethod::Linear.interpolate(&samples, -0.5), None);
+
+        let y = InterpMethod::Lagrange(3)
+            .interpolate(&samples, 4.5)
+            .unwrap();
+        assert!((y - (4.5_f64.powi(3) - 9.0)).abs() < 1e-9);
+
+        let y = InterpMethod::Lagrange(3)
+            .interpolate(&samples, 8.5)
+            .unwrap();
+        assert!((y - (8.5_f64.powi(3) - 17.0)).abs() < 1e-9);
+
+        assert_eq!(InterpMethod::Lagrange(11).interpolate(&samples, 4.5), None);
+        assert_eq!(
+            InterpMethod::Lagrange(usize::MAX).interpolate(&samples, 4.5),
+            None
+        );
+
+        for method in [
+            InterpMethod::Linear,
+            InterpMethod::Lagrange(3),
+            InterpMethod::CubicHermite,
+        ] {
+            for x in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+                assert_eq!(method.interpolate(&samples, x), None, "{}({})", method, x);
+            }
+        }
+
+        // finite difference tangents are off by h^2 on cubics,
+        // which cancels out at mid interval
+        let y = InterpMethod::CubicHermite
+            .interpolate(&samples, 4.5)
+            .unwrap();
+        assert!((y - (4.5_f64.powi(3) - 9.0)).abs() < 1e-9);
+
+        // y = x^2, finite difference tangents are exact within the samples
+        let squares = (0..10)
+            .map(|i| (i as f64, (i * i) as f64))
+            .collect::<Vec<_>>();
+
+        for x in [1.5, 4.25, 7.75] {
+            let y = InterpMethod::CubicHermite.interpolate(&squares, x).unwrap();
+            assert!((y - x * x).abs() < 1e-9, "hermite({}) = {}", x, y);
+        }
+
+        let t0 = Epoch::from_str("2020-01-01T00:00:00 UTC").unwrap();
+        let samples = [(t0, 0.0), (t0 + Duration::from_seconds(30.0), 30.0)];
+        let t = t0 + Duration::from_seconds(10.0);
+        assert_eq!(
+            InterpMethod::Linear.interpolate_epoch(&samples, t),
+            Some(10.0)
+        );
+    }
+
+    #[test]
+    fn interp_parsing() {
+        for (desc, method, len, item) in [
+            (
+                "linear:2020-01-01T00:00:00 UTC..2020-01-01T00:10:00 UTC/1 min",
+                InterpMethod::Linear,
+                10,
+                None,
+            ),
+            (
+                "lagrange(7):2020-01-01T00:00:00 UTC..=2020-01-01T00:10:00 UTC/30 s:C1C",
+                InterpMethod::Lagrange(7),
+                21,
+                Some(FilterItem::ObservableItem(vec![
+                    Observable::from_str("C1C").unwrap(),
+                ])),
+            ),
+            (
+                "hermite:2020-01-01T00:00:00 UTC,2020-01-01T00:00:30 UTC:G08",
+                InterpMethod::CubicHermite,
+                2,
+                Some(FilterItem::from_str("G08").unwrap()),
+            ),
+        ] {
+            let filter = InterpFilter::from_str(desc).unwrap();
+            assert_eq!(filter.method, method);
+            assert_eq!(filter.series.epochs().len(), len, "for \"{}\"", desc);
+            assert_eq!(filter.item, item);
+            assert_eq!(InterpFilter::from_str(&filter.to_string()).unwrap(), filter);
+        }
+
+        for period in [Duration::ZERO, Duration::from_seconds(-30.0)] {
+            let series = InterpSeries::Interval {
+                interval: Interval::from_str("2020-01-01T00:00:00 UTC..2020-01-01T00:10:00 UTC")
+                    .unwrap(),
+                period,
+            };
+            assert!(series.epochs().is_empty());
+        }
+
+        for desc in [
+            "linear",
+            "cubic:2020-01-01T00:00:00 UTC",
+            "lagrange(0):2020-01-01T00:00:00 UTC",
+            "lagrange(21):2020-01-01T00:00:00 UTC",
+            "lagrange(18446744073709551615):2020-01-01T00:00:00 UTC",
+            "interp",
+            "linear:2020-01-01T00:00:00 UTC..2020-01-01T00:10:00 UTC/0 s",
+        ] {
+            assert!(
+                InterpFilter::from_str(desc).is_err(),
+                "parsed invalid interpolation filter \"{}\"",
+                desc
+            );
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Intervals, used by range [MaskOperand](crate::processing::MaskOperand)s
+use std::str::FromStr;
+use thiserror::Error;
+
+/// [Interval] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid interval \"{0}\"")]
+    InvalidInterval(String),
+    #[error("invalid interval bound \"{0}\"")]
+    InvalidBound(String),
+    #[error("interval start should not be greater than its end")]
+    UnorderedBounds,
+}
+
+/// [Interval] between two bounds, that are either inclusive or exclusive.
+/// It is described either:
+/// - with the Rust range syntax: `10..60` is inclusive/exclusive,
+///   while `10..=60` is fully inclusive.
+/// - with the mathematical notation: `[30,45)`, `(30,45]`, `[30,45]`, `(30,45)`,
+///   where square brackets mark inclusive bounds.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Interval<T> {
+    /// Start (lower bound)
+    pub start: T,
+    /// End (upper bound)
+    pub end: T,
+    /// True when start is included
+    pub start_inclusive: bool,
+    /// True when end is included
+    pub end_inclusive: bool,
+}
+
+impl<T: PartialOrd> Interval<T> {
+    /// Builds a new inclusive/exclusive [Interval], like `start..end`.
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            start,
+            end,
+            start_inclusive: true,
+            end_inclusive: false,
+        }
+    }
+
+    /// Builds a new fully inclusive [Interval], like `start..=end`.
+    pub fn inclusive(start: T, end: T) -> Self {
+        Self {
+            start,
+            end,
+            start_inclusive: true,
+            end_inclusive: true,
+        }
+    }
+
+    /// Returns true if value lies within this [Interval]
+    pub fn contains(&self, value: &T) -> bool {
+        let after_start = if self.start_inclusive {
+            *value >= self.start
+        } else {
+            *value > self.start
+        };
+        let before_end = if self.end_inclusive {
+            *value <= self.end
+        } else {
+            *value < self.end
+        };
+        after_start && before_end
+    }
+
+    /// Returns true if both bounds lie within `[min, max]`
+    pub(crate) fn within(&self, min: T, max: T) -> bool {
+        self.start >= min && self.end <= max
+    }
+}
+
+impl<T> Interval<T> {
+    /// Returns true if given description looks like an [Interval],
+    /// this does not verify it is valid.
+    pub(crate) fn matches(content: &str) -> bool {
+        let c = content.trim();
+        c.contains("..") || (c.starts_with(['[', '(']) && c.ends_with([']', ')']))
+    }
+}
+
+impl<T: FromStr + PartialOrd> FromStr for Interval<T> {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+
+        let parse_bound = |bound: &str| {
+            T::from_str(bound.trim()).map_err(|_| Error::InvalidBound(bound.trim().to_string()))
+        };
+
+        let interval = if let Some((start, end)) = c.split_once("..=") {
+            Self::inclusive(parse_bound(start)?, parse_bound(end)?)
+        } else if let Some((start, end)) = c.split_once("..") {
+            Self::new(parse_bound(start)?, parse_bound(end)?)
+        } else if c.starts_with(['[', '(']) && c.ends_with([']', ')']) && c.len() > 1 {
+            let inner = &c[1..c.len() - 1];
+            let (start, end) = inner
+                .split_once(',')
+                .ok_or(Error::InvalidInterval(c.to_string()))?;
+            Self {
+                start: parse_bound(start)?,
+                end: parse_bound(end)?,
+                start_inclusive: c.starts_with('['),
+                end_inclusive: c.ends_with(']'),
+            }
+        } else {
+            return Err(Error::InvalidInterval(c.to_string()));
+        };
+
+        if interval.start > interval.end {
+            return Err(Error::UnorderedBounds);
+        }
+
+        Ok(interval)
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Interval<T> {
+    /// Formats this [Interval] so it may be parsed back with [Interval::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (self.start_inclusive, self.end_inclusive) {
+            (true, false) => write!(f, "{}..{}", self.start, self.end),
+            (true, true) => write!(f, "{}..={}", self.start, self.end),
+            (false, end_inclusive) => write!(
+                f,
+                "({}, {}{}",
+                self.start,
+                self.end,
+                if end_inclusive { ']' } else { ')' }
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hifitime::Epoch;
+
+    #[test]
+    fn interval_parsing() {
+        let interval = Interval::<f64>::from_str("10..60").unwrap();
+        assert_eq!(interval, Interval::new(10.0, 60.0));
+        assert!(interval.contains(&10.0));
+        assert!(!interval.contains(&60.0));
+
+        let interval = Interval::<f64>::from_str(" 10 ..= 60").unwrap();
+        assert_eq!(interval, Interval::inclusive(10.0, 60.0));
+        assert!(interval.contains(&60.0));
+
+        let interval = Interval::<f64>::from_str("[30,45)").unwrap();
+        assert_eq!(interval, Interval::new(30.0, 45.0));
+
+        let interval = Interval::<f64>::from_str("(30, 45]").unwrap();
+        assert!(!interval.contains(&30.0));
+        assert!(interval.contains(&45.0));
+
+        let interval =
+            Interval::<Epoch>::from_str("2020-01-01T00:00:00 UTC..2020-01-01T06:00:00 UTC")
+                .unwrap();
+        assert!(interval.contains(&Epoch::from_str("2020-01-01T03:00:00 UTC").unwrap()));
+
+        for desc in ["10", "60..10", "[30,45", "[30;45]", "x..10"] {
+            assert!(
+                Interval::<f64>::from_str(desc).is_err(),
+                "parsed invalid interval \"{}\"",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    fn interval_display() {
+        for desc in ["10..60", "10..=60", "(30, 45]", "(30, 45)"] {
+            let interval = Interval::<f64>::from_str(desc).unwrap();
+            assert_eq!(interval.to_string(), desc);
+        }
+    }
+}
@@ -3,11 +3,26 @@ use thiserror::Error;
 
 use gnss_rs::{
     constellation::ParsingError as ConstellationParsingError,
-    prelude::{Constellation, SV},
+    prelude::{COSPAR, Constellation, DOMES, SV},
     sv::ParsingError as SVParsingError,
 };
 
-use hifitime::{Duration, Epoch, ParsingError as EpochParsingError};
+use hifitime::{Duration, Epoch, ParsingError as EpochParsingError, TimeScale};
+
+use crate::processing::{
+    CalendarError, Carrier, ClockError, ClockValue, DayOfYear, DaysOfWeek, Diagnostic,
+    DiagnosticExpectation as Expectation, EpochFlag, FlagError, Interval, NavRange, NavValue,
+    NavigationError, Observable, OrbitClass, Region, RegionError, RelativeEpoch, RelativeError,
+    RelativeSpan, SNRTemplate, SNRTemplateError, SatelliteError, TimeOfDay, TimeOfWeek,
+    calendar::{doy_period, mjd_epoch, mjd_period, periods_interval, split_timescale, week_period},
+    diagnostic::{CONSTELLATIONS, SBAS_SERVICES, suggest},
+    flag::{parse_lli, parse_ssi},
+    relative::is_relative,
+    satellite::parse_block,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Error)]
 pub enum ItemError {
@@ -25,6 +40,24 @@ pub enum ItemError {
     InvalidElevationAngle,
     #[error("invalid azimuth angle description (0 <= a <= 360)")]
     InvalidAzimuthAngle,
+    #[error("invalid frequency description")]
+    InvalidFrequency,
+    #[error("invalid calendar item: {0}")]
+    Calendar(#[from] CalendarError),
+    #[error("invalid observation flag: {0}")]
+    Flag(#[from] FlagError),
+    #[error("invalid clock value: {0}")]
+    Clock(#[from] ClockError),
+    #[error("invalid satellite metadata: {0}")]
+    Satellite(#[from] SatelliteError),
+    #[error("invalid navigation field: {0}")]
+    Navigation(#[from] NavigationError),
+    #[error("invalid snr template: {0}")]
+    SNRTemplate(#[from] SNRTemplateError),
+    #[error("invalid relative epoch: {0}")]
+    Relative(#[from] RelativeError),
+    #[error("invalid region: {0}")]
+    Region(#[from] RegionError),
     #[error("invalid float number")]
     FloatParsing(#[from] ParseFloatError),
     #[error("sv item parsing")]
@@ -33,29 +66,281 @@ pub enum ItemError {
     ConstellationParing(#[from] ConstellationParsingError),
     #[error("duration item parsing")]
     InvalidDurationItem(#[from] EpochParsingError),
+    #[error("{0}")]
+    Diagnostic(#[from] Diagnostic),
+}
+
+impl ItemError {
+    /// Returns the [Diagnostic] attached to this error, if any.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            Self::Diagnostic(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
 }
 
 /// [FilterItem] represents items that filters or other
 /// GNSS processing ops may apply to.
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FilterItem {
     /// Epoch Item
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "epoch", with = "crate::processing::schema::display_fromstr")
+    )]
     EpochItem(Epoch),
     /// Duration Item
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "duration", with = "crate::processing::schema::duration")
+    )]
     DurationItem(Duration),
     /// SNR value, expressed in [dB]
+    #[cfg_attr(feature = "serde", serde(rename = "snr"))]
     SNRItem(f64),
+    /// Elevation dependent SNR threshold, see [SNRTemplate]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "snr_template",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    SNRTemplateItem(SNRTemplate),
     /// Elevation Angle Item in degrees, 0 <= e <= 90°
+    #[cfg_attr(feature = "serde", serde(rename = "elevation"))]
     ElevationItem(f64),
     /// Azimuth Angle Item in degrees, 0 <= a <= 360°
+    #[cfg_attr(feature = "serde", serde(rename = "azimuth"))]
     AzimuthItem(f64),
+    /// Recurring [TimeOfDay]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "time_of_day",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    TimeOfDayItem(TimeOfDay),
+    /// Recurring [DaysOfWeek]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "day_of_week",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    DayOfWeekItem(DaysOfWeek),
+    /// Recurring [DayOfYear]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "day_of_year",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    DayOfYearItem(DayOfYear),
+    /// Recurring [TimeOfWeek]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "time_of_week",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    TimeOfWeekItem(TimeOfWeek),
+    /// Frequency, expressed in [MHz]
+    #[cfg_attr(feature = "serde", serde(rename = "frequency"))]
+    FrequencyItem(f64),
+    /// Loss of Lock Indicator bits, 0 <= lli <= 7, see [LLI_MAX](crate::LLI_MAX).
+    /// Masks test bits (see [lli_matches](crate::lli_matches)): `lli=1` retains observations
+    /// with a loss of lock, `lli!=0` retains observations with any flag raised.
+    /// Ordering operands do not apply.
+    #[cfg_attr(feature = "serde", serde(rename = "lli"))]
+    LliItem(u8),
+    /// Signal Strength Indicator, 0 <= ssi <= 9, see [SSI_MAX](crate::SSI_MAX).
+    /// Masks compare the SSI value: `ssi>=5` retains observations with a good signal to noise ratio.
+    #[cfg_attr(feature = "serde", serde(rename = "ssi"))]
+    SsiItem(u8),
+    /// List of [EpochFlag]s, retaining (or discarding) epochs flagged accordingly
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "epochflag",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    EpochFlagItem(Vec<EpochFlag>),
+    /// Navigation message field value
+    #[cfg_attr(feature = "serde", serde(rename = "nav"))]
+    NavItem(NavValue),
+    /// Navigation message field [Interval] of values
+    #[cfg_attr(feature = "serde", serde(rename = "nav_range"))]
+    NavRangeItem(NavRange),
+    /// Geographic [Region]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "region", with = "crate::processing::schema::display_fromstr")
+    )]
+    RegionItem(Region),
+    /// Epoch [Interval]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "epoch_range",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    EpochRangeItem(Interval<Epoch>),
+    /// [Epoch] relative to the dataset, see [RelativeEpoch]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "relative_epoch",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    RelativeEpochItem(RelativeEpoch),
+    /// Period at the start or end of the dataset, see [RelativeSpan]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "relative_span",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    RelativeSpanItem(RelativeSpan),
+    /// SNR [Interval], expressed in [dB]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "snr_range",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    SNRRangeItem(Interval<f64>),
+    /// Elevation Angle [Interval] in degrees, within [0, 90]°
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "elevation_range",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    ElevationRangeItem(Interval<f64>),
+    /// Azimuth Angle [Interval] in degrees, within [0, 360]°
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "azimuth_range",
+            with = "crate::processing::schema::display_fromstr"
+        )
+    )]
+    AzimuthRangeItem(Interval<f64>),
     /// List of spacecrafts described as [SV]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "sv", with = "crate::processing::schema::display_fromstr_vec")
+    )]
     SvItem(Vec<SV>),
     /// List of [Constellation]s
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "constellation",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
     ConstellationItem(Vec<Constellation>),
+    /// Selection of both [Constellation]s and [SV]s, described as a mixed list
+    #[cfg_attr(feature = "serde", serde(rename = "selection"))]
+    SelectionItem {
+        /// Selected [Constellation]s
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::processing::schema::display_fromstr_vec")
+        )]
+        constellations: Vec<Constellation>,
+        /// Selected [SV]s
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::processing::schema::display_fromstr_vec")
+        )]
+        svs: Vec<SV>,
+    },
+    /// List of [Carrier]s
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "carrier",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    CarrierItem(Vec<Carrier>),
+    /// List of station marker names, like `ABMF`
+    #[cfg_attr(feature = "serde", serde(rename = "marker"))]
+    MarkerItem(Vec<String>),
+    /// List of [DOMES] site identification numbers
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "domes",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    DomesItem(Vec<DOMES>),
+    /// List of [COSPAR] launch identifiers
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "cospar",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    CosparItem(Vec<COSPAR>),
+    /// List of SBAS services, described as [Constellation]s (like [Constellation::EGNOS]).
+    /// SBAS vehicles are resolved to their service by gnss-rs, so it
+    /// matches the [SV] constellation. [Constellation::SBAS] designates all services.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "sbas",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    SbasItem(Vec<Constellation>),
+    /// List of [OrbitClass]es, resolved with a [SatelliteDB](crate::SatelliteDB)
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "orbit",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    OrbitItem(Vec<OrbitClass>),
+    /// List of satellite blocks (or generations) like `IIIA` or `FOC`,
+    /// resolved with a [SatelliteDB](crate::SatelliteDB)
+    #[cfg_attr(feature = "serde", serde(rename = "block"))]
+    BlockItem(Vec<String>),
+    /// List of [Observable]s, that may be patterns like `L*`
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "observable",
+            with = "crate::processing::schema::display_fromstr_vec"
+        )
+    )]
+    ObservableItem(Vec<Observable>),
     /// Clock Offset Item
+    #[cfg_attr(feature = "serde", serde(rename = "clock"))]
     ClockItem,
+    /// Clock state value (offset, drift or drift rate), see [ClockValue]
+    #[cfg_attr(feature = "serde", serde(rename = "clock_value"))]
+    ClockValueItem(ClockValue),
     /// List of complex items originally described as Strings
+    #[cfg_attr(feature = "serde", serde(rename = "complex"))]
     ComplexItem(Vec<String>),
 }
 
@@ -65,75 +350,323 @@ impl std::ops::BitOrAssign for FilterItem {
     }
 }
 
+/// Concatenates two lists, see [FilterItem::bitor]
+fn concat<T>(mut lhs: Vec<T>, rhs: Vec<T>) -> Vec<T> {
+    lhs.extend(rhs);
+    lhs
+}
+
 impl std::ops::BitOr for FilterItem {
     type Output = Self;
+    /// Concatenates two lists of the same kind. A [FilterItem::SelectionItem]
+    /// also absorbs constellation and satellite lists.
+    /// Any other combination (including non list items) is not a union:
+    /// the left hand side is returned and the right hand side is dropped.
+    /// Use [crate::processing::FilterExpr] to combine masks of any kind.
     fn bitor(self, rhs: Self) -> Self {
-        match self {
-            Self::SvItem(ref lhs) => match rhs {
-                Self::SvItem(rhs) => {
-                    let mut lhs = lhs.clone();
-                    for r in rhs {
-                        lhs.push(r);
-                    }
-                    Self::SvItem(lhs)
-                }
-                _ => self.clone(),
-            },
-            Self::ConstellationItem(ref lhs) => match rhs {
-                Self::ConstellationItem(rhs) => {
-                    let mut lhs = lhs.clone();
-                    for r in rhs {
-                        lhs.push(r);
-                    }
-                    Self::ConstellationItem(lhs)
-                }
-                _ => self.clone(),
+        match (self, rhs) {
+            (Self::SvItem(lhs), Self::SvItem(rhs)) => Self::SvItem(concat(lhs, rhs)),
+            (Self::CarrierItem(lhs), Self::CarrierItem(rhs)) => Self::CarrierItem(concat(lhs, rhs)),
+            (Self::MarkerItem(lhs), Self::MarkerItem(rhs)) => Self::MarkerItem(concat(lhs, rhs)),
+            (Self::DomesItem(lhs), Self::DomesItem(rhs)) => Self::DomesItem(concat(lhs, rhs)),
+            (Self::CosparItem(lhs), Self::CosparItem(rhs)) => Self::CosparItem(concat(lhs, rhs)),
+            (Self::SbasItem(lhs), Self::SbasItem(rhs)) => Self::SbasItem(concat(lhs, rhs)),
+            (Self::EpochFlagItem(lhs), Self::EpochFlagItem(rhs)) => {
+                Self::EpochFlagItem(concat(lhs, rhs))
+            }
+            (Self::OrbitItem(lhs), Self::OrbitItem(rhs)) => Self::OrbitItem(concat(lhs, rhs)),
+            (Self::BlockItem(lhs), Self::BlockItem(rhs)) => Self::BlockItem(concat(lhs, rhs)),
+            (Self::ObservableItem(lhs), Self::ObservableItem(rhs)) => {
+                Self::ObservableItem(concat(lhs, rhs))
+            }
+            (Self::ConstellationItem(lhs), Self::ConstellationItem(rhs)) => {
+                Self::ConstellationItem(concat(lhs, rhs))
+            }
+            (
+                Self::SelectionItem {
+                    constellations,
+                    svs,
+                },
+                rhs,
+            ) => match rhs {
+                Self::SelectionItem {
+                    constellations: rhs_constellations,
+                    svs: rhs_svs,
+                } => Self::SelectionItem {
+                    constellations: concat(constellations, rhs_constellations),
+                    svs: concat(svs, rhs_svs),
+                },
+                Self::ConstellationItem(rhs) => Self::SelectionItem {
+                    constellations: concat(constellations, rhs),
+                    svs,
+                },
+                Self::SvItem(rhs) => Self::SelectionItem {
+                    constellations,
+                    svs: concat(svs, rhs),
+                },
+                _ => Self::SelectionItem {
+                    constellations,
+                    svs,
+                },
             },
-            _ => self.clone(),
+            // not permitted on type mismatch
+            (lhs, _) => lhs,
         }
     }
 }
 
-pub(crate) fn parse_sv_list(items: Vec<&str>) -> Result<Vec<SV>, SVParsingError> {
-    let mut ret: Vec<SV> = Vec::with_capacity(items.len());
-    for item in items {
-        let sv = SV::from_str(item.trim())?;
-        ret.push(sv);
+/// Splits a comma separated list into trimmed tokens,
+/// with their byte offset within the list.
+pub(crate) fn list_tokens(content: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    content
+        .split(',')
+        .map(|token| {
+            let start = offset + token.len() - token.trim_start().len();
+            offset += token.len() + 1;
+            (start, token.trim())
+        })
+        .collect()
+}
+
+/// Returns the valid PRN numbers of given [Constellation], if they are known.
+/// SBAS vehicles use PRN numbers minus 100, as in RINEX.
+fn prn_bounds(constellation: &Constellation) -> Option<(u8, u8)> {
+    match constellation {
+        Constellation::GPS => Some((1, 32)),
+        Constellation::Glonass => Some((1, 27)),
+        Constellation::Galileo => Some((1, 36)),
+        Constellation::BeiDou => Some((1, 63)),
+        Constellation::QZSS => Some((1, 10)),
+        Constellation::IRNSS => Some((1, 14)),
+        c if c.is_sbas() => Some((20, 58)),
+        _ => None,
+    }
+}
+
+/// Returns all the [SV]s of given [Constellation], from `first` to `last` PRN.
+/// [SV]s are parsed so SBAS vehicles are resolved to their service by gnss-rs.
+fn sv_span(constellation: &Constellation, first: u8, last: u8) -> Vec<SV> {
+    (first..=last)
+        .filter_map(|prn| SV::from_str(&format!("{:x}{:02}", constellation, prn)).ok())
+        .collect()
+}
+
+/// Returns true if this token looks like a set of satellites:
+/// a range (`G01-G10`), a constellation wildcard (`E*`), or an exclusion (`!R12`).
+fn is_sv_set(token: &str) -> bool {
+    let (excluded, set) = match token.strip_prefix('!') {
+        Some(set) => (true, set.trim()),
+        None => (false, token),
+    };
+    match set.split_once('-') {
+        Some((first, last)) => SV::from_str(first).is_ok() && SV::from_str(last).is_ok(),
+        None => (set.len() == 2 && set.ends_with('*')) || (excluded && SV::from_str(set).is_ok()),
     }
-    Ok(ret)
 }
 
-pub(crate) fn parse_gnss_list(
-    items: Vec<&str>,
-) -> Result<Vec<Constellation>, ConstellationParsingError> {
-    let mut ret: Vec<Constellation> = Vec::with_capacity(items.len());
-    for item in items {
-        let c = Constellation::from_str(item.trim())?;
-        ret.push(c);
+/// Parses a set of satellites (see [is_sv_set]), validating PRN bounds.
+/// Returns true when these satellites are to be excluded.
+fn parse_sv_set(token: &str) -> Option<(bool, Vec<SV>)> {
+    let (excluded, set) = match token.strip_prefix('!') {
+        Some(set) => (true, set.trim()),
+        None => (false, token),
+    };
+    let svs = if let Some((first, last)) = set.split_once('-') {
+        let (first, last) = (SV::from_str(first).ok()?, SV::from_str(last).ok()?);
+        let constellation = Constellation::from_str(&set[..1]).ok()?;
+        let (min, max) = prn_bounds(&constellation)?;
+        let same = format!("{:x}", first.constellation) == format!("{:x}", last.constellation);
+        if !same || first.prn < min || last.prn > max || first.prn > last.prn {
+            return None;
+        }
+        sv_span(&constellation, first.prn, last.prn)
+    } else if let Some(letter) = set.strip_suffix('*') {
+        let constellation = Constellation::from_str(letter).ok()?;
+        let (min, max) = prn_bounds(&constellation)?;
+        sv_span(&constellation, min, max)
+    } else {
+        let sv = SV::from_str(set).ok()?;
+        let (min, max) = prn_bounds(&sv.constellation)?;
+        if sv.prn < min || sv.prn > max {
+            return None;
+        }
+        vec![sv]
+    };
+    Some((excluded, svs))
+}
+
+/// Expands a list of satellites, satellite sets and constellations into
+/// a single set of satellites. Excluded satellites are removed from the
+/// satellites designated by all other tokens.
+fn parse_sv_set_list(items: &[(usize, &str)]) -> Result<Vec<SV>, Diagnostic> {
+    let (mut included, mut excluded) = (Vec::<SV>::new(), Vec::<SV>::new());
+    for (offset, item) in items {
+        let invalid = || Diagnostic::new(*offset, item, Expectation::SvSet);
+        let (exclusion, svs) = match Constellation::from_str(item) {
+            Ok(constellation) if !is_sv_set(item) => {
+                let (min, max) = prn_bounds(&constellation).ok_or_else(invalid)?;
+                (false, sv_span(&constellation, min, max))
+            }
+            _ => parse_sv_set(item).ok_or_else(invalid)?,
+        };
+        if exclusion {
+            excluded.extend(svs);
+        } else {
+            included.extend(svs);
+        }
+    }
+    if included.is_empty() {
+        let (offset, item) = items[0];
+        return Err(Diagnostic::new(offset, item, Expectation::SvSet));
+    }
+    let mut ret: Vec<SV> = Vec::with_capacity(included.len());
+    for sv in included {
+        if !excluded.contains(&sv) && !ret.contains(&sv) {
+            ret.push(sv);
+        }
     }
     Ok(ret)
 }
 
+/// Parses a list of items described by their [FromStr] implementation.
+/// The [Diagnostic] designates the first invalid item.
+pub(crate) fn parse_list<T: FromStr>(
+    items: &[(usize, &str)],
+    expected: Expectation,
+) -> Result<Vec<T>, Diagnostic> {
+    parse_list_with(items, expected, |item| T::from_str(item).ok())
+}
+
+/// Parses a list of items with given parser, see [parse_list].
+fn parse_list_with<T>(
+    items: &[(usize, &str)],
+    expected: Expectation,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Vec<T>, Diagnostic> {
+    items
+        .iter()
+        .map(|(offset, item)| {
+            parse(item).ok_or_else(|| Diagnostic::new(*offset, item, expected.clone()))
+        })
+        .collect()
+}
+
+fn parse_carrier_list(items: &[(usize, &str)]) -> Result<Vec<Carrier>, Diagnostic> {
+    let names = Carrier::ALL.map(|c| c.to_string());
+    let names = names.iter().map(|c| c.as_str()).collect::<Vec<_>>();
+    parse_list(items, Expectation::Carrier).map_err(|e| e.with_suggestion(&names))
+}
+
+/// Station marker names are case insensitive, and may not contain whitespaces
+/// nor delimiters: they are formatted in uppercase.
+fn parse_marker(item: &str) -> Option<String> {
+    let valid = !item.is_empty()
+        && item
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| item.to_ascii_uppercase())
+}
+
+/// Kind of a single list token, used in the type guessing process
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum TokenKind {
+    Epoch,
+    SV,
+    SvSet,
+    Constellation,
+    Observable,
+    Carrier,
+    Complex,
+}
+
+impl TokenKind {
+    fn classify(token: &str) -> Self {
+        if Epoch::from_str(token).is_ok() {
+            Self::Epoch
+        } else if SV::from_str(token).is_ok() {
+            Self::SV
+        } else if Constellation::from_str(token).is_ok() {
+            Self::Constellation
+        } else if Observable::from_str(token).is_ok() {
+            Self::Observable
+        } else if Carrier::from_str(token).is_ok() {
+            Self::Carrier
+        } else if is_sv_set(token) {
+            Self::SvSet
+        } else {
+            Self::Complex
+        }
+    }
+
+    /// Returns true if a list of this family may contain given token kind.
+    /// Complex items may be scoped by constellations, but not by satellites.
+    fn accepts(&self, kind: Self) -> bool {
+        match self {
+            Self::Epoch => kind == Self::Epoch,
+            Self::Observable => kind == Self::Observable,
+            Self::Carrier => kind == Self::Carrier,
+            Self::Complex => matches!(kind, Self::Complex | Self::Constellation),
+            Self::SV | Self::SvSet | Self::Constellation => {
+                matches!(kind, Self::SV | Self::SvSet | Self::Constellation)
+            }
+        }
+    }
+
+    /// Describes the tokens a list of this family may contain
+    fn expected(&self) -> &'static str {
+        match self {
+            Self::Epoch => "epoch",
+            Self::Observable => "observable",
+            Self::Carrier => "carrier",
+            Self::Complex => "complex item or constellation",
+            Self::SV | Self::SvSet | Self::Constellation => "satellite or constellation",
+        }
+    }
+}
+
 fn parse_float_payload(content: &str) -> Result<f64, ParseFloatError> {
     f64::from_str(content.trim())
 }
 
+/// Parses a frequency, with optional unit (MHz by default), into MHz.
+fn parse_frequency_payload(content: &str) -> Option<f64> {
+    let c = content.trim();
+    let split = c.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(c.len());
+    let (value, unit) = c.split_at(split);
+    let scaling = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "mhz" => 1.0,
+        "hz" => 1.0E-6,
+        "khz" => 1.0E-3,
+        "ghz" => 1.0E3,
+        _ => return None,
+    };
+    let value = f64::from_str(value.trim()).ok()?;
+    Some(value * scaling)
+}
+
+/// Parses a single [Duration], that must be described with a unit.
+fn parse_duration_payload(content: &str) -> Option<Duration> {
+    if content.contains(',') || !content.contains(char::is_alphabetic) {
+        return None;
+    }
+    Duration::from_str(content.trim()).ok()
+}
+
 impl FilterItem {
     pub(crate) fn from_elevation(content: &str) -> Result<Self, ItemError> {
-        if let Ok(float) = parse_float_payload(content) {
-            if float >= 0.0 && float <= 90.0 {
-                return Ok(Self::AzimuthItem(float));
-            }
-        }
-        Err(ItemError::InvalidElevationAngle)
+        parse_float_payload(content)
+            .ok()
+            .filter(|float| (0.0..=90.0).contains(float))
+            .map(Self::ElevationItem)
+            .ok_or(ItemError::InvalidElevationAngle)
     }
     pub(crate) fn from_azimuth(content: &str) -> Result<Self, ItemError> {
-        if let Ok(float) = parse_float_payload(content) {
-            if float >= 0.0 && float <= 360.0 {
-                return Ok(Self::AzimuthItem(float));
-            }
-        }
-        Err(ItemError::InvalidAzimuthAngle)
+        parse_float_payload(content)
+            .ok()
+            .filter(|float| (0.0..=360.0).contains(float))
+            .map(Self::AzimuthItem)
+            .ok_or(ItemError::InvalidAzimuthAngle)
     }
     pub(crate) fn from_snr(content: &str) -> Result<Self, ItemError> {
         if let Ok(float) = parse_float_payload(content) {
@@ -142,6 +675,185 @@ impl FilterItem {
             Err(ItemError::InvalidSNR)
         }
     }
+    pub(crate) fn from_snr_template(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::SNRTemplateItem(SNRTemplate::from_str(content)?))
+    }
+    pub(crate) fn from_frequency(content: &str) -> Result<Self, ItemError> {
+        match parse_frequency_payload(content) {
+            Some(frequency) if frequency > 0.0 => Ok(Self::FrequencyItem(frequency)),
+            _ => Err(ItemError::InvalidFrequency),
+        }
+    }
+    pub(crate) fn from_time_of_day(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::TimeOfDayItem(TimeOfDay::from_str(content)?))
+    }
+    pub(crate) fn from_days_of_week(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::DayOfWeekItem(DaysOfWeek::from_str(content)?))
+    }
+    /// Parses a week number, with optional [TimeScale] (GPST by default),
+    /// into the [Interval] it covers. Weeks are counted from the reference epoch of the [TimeScale].
+    pub(crate) fn from_week(content: &str) -> Result<Self, ItemError> {
+        let (week, timescale) = split_timescale(content, TimeScale::GPST);
+        let week = u32::from_str(week).map_err(|_| CalendarError::InvalidWeek(week.to_string()))?;
+        Ok(Self::EpochRangeItem(week_period(week, timescale)))
+    }
+    pub(crate) fn from_week_range(content: &str) -> Result<Self, ItemError> {
+        let (weeks, timescale) = split_timescale(content, TimeScale::GPST);
+        let weeks = Interval::<u32>::from_str(weeks)
+            .map_err(|_| CalendarError::InvalidWeek(weeks.to_string()))?;
+        let weeks = Interval {
+            start: weeks.start as i64,
+            end: weeks.end as i64,
+            start_inclusive: weeks.start_inclusive,
+            end_inclusive: weeks.end_inclusive,
+        };
+        Ok(Self::EpochRangeItem(periods_interval(&weeks, |week| {
+            week_period(week as u32, timescale)
+        })))
+    }
+    /// Parses a Modified Julian Date, with optional [TimeScale] (UTC by default).
+    /// Integer dates are converted to the [Interval] of the entire day.
+    pub(crate) fn from_mjd(content: &str) -> Result<Self, ItemError> {
+        let (mjd, timescale) = split_timescale(content, TimeScale::UTC);
+        match (i64::from_str(mjd), f64::from_str(mjd)) {
+            (Ok(mjd), _) if mjd >= 0 => Ok(Self::EpochRangeItem(mjd_period(mjd, timescale))),
+            (_, Ok(mjd)) if mjd >= 0.0 => Ok(Self::EpochItem(mjd_epoch(mjd, timescale))),
+            _ => Err(CalendarError::InvalidMJD(mjd.to_string()).into()),
+        }
+    }
+    pub(crate) fn from_mjd_range(content: &str) -> Result<Self, ItemError> {
+        let (mjd, timescale) = split_timescale(content, TimeScale::UTC);
+        let interval = Interval::<i64>::from_str(mjd)
+            .ok()
+            .filter(|interval| interval.start >= 0)
+            .ok_or(CalendarError::InvalidMJD(mjd.to_string()))?;
+        Ok(Self::EpochRangeItem(periods_interval(&interval, |mjd| {
+            mjd_period(mjd, timescale)
+        })))
+    }
+    /// Parses a day of year, either recurring (`DDD`), or within a given year (`YYYY-DDD`),
+    /// with optional [TimeScale] (UTC by default).
+    pub(crate) fn from_doy(content: &str) -> Result<Self, ItemError> {
+        let (doy, timescale) = split_timescale(content, TimeScale::UTC);
+        match doy.split_once(['-', '/']) {
+            Some((year, day)) => {
+                let invalid = || CalendarError::InvalidDayOfYear(doy.to_string());
+                let year = i32::from_str(year.trim()).map_err(|_| invalid())?;
+                let day = u16::from_str(day.trim()).map_err(|_| invalid())?;
+                let period = doy_period(year, day, timescale).ok_or_else(invalid)?;
+                Ok(Self::EpochRangeItem(period))
+            }
+            None => Ok(Self::DayOfYearItem(DayOfYear::from_str(content)?)),
+        }
+    }
+    pub(crate) fn from_tow(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::TimeOfWeekItem(TimeOfWeek::from_str(content)?))
+    }
+    pub(crate) fn from_region(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::RegionItem(Region::from_str(content)?))
+    }
+    pub(crate) fn from_carriers(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::CarrierItem(parse_carrier_list(&list_tokens(
+            content,
+        ))?))
+    }
+    /// Parses a list of satellites, satellite sets and constellations,
+    /// where `C*` and `S*` designate BeiDou and SBAS vehicles.
+    pub(crate) fn from_sv_sets(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::SvItem(parse_sv_set_list(&list_tokens(content))?))
+    }
+    /// Parses a list of [Observable]s, including the RINEX 2 observables
+    /// that also describe satellites, like `C1`.
+    pub(crate) fn from_observables(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        Ok(Self::ObservableItem(parse_list(
+            &items,
+            Expectation::Observable,
+        )?))
+    }
+    pub(crate) fn from_markers(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        Ok(Self::MarkerItem(parse_list_with(
+            &items,
+            Expectation::Marker,
+            parse_marker,
+        )?))
+    }
+    pub(crate) fn from_domes(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        Ok(Self::DomesItem(parse_list(&items, Expectation::DOMES)?))
+    }
+    pub(crate) fn from_cospar(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        Ok(Self::CosparItem(parse_list(&items, Expectation::COSPAR)?))
+    }
+    pub(crate) fn from_sbas(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        let sbas = parse_list_with(&items, Expectation::SBAS, |item| {
+            Constellation::from_str(item).ok().filter(|c| c.is_sbas())
+        })
+        .map_err(|e| e.with_suggestion(SBAS_SERVICES))?;
+        Ok(Self::SbasItem(sbas))
+    }
+    pub(crate) fn from_lli(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::LliItem(parse_lli(content)?))
+    }
+    pub(crate) fn from_ssi(content: &str) -> Result<Self, ItemError> {
+        Ok(Self::SsiItem(parse_ssi(content)?))
+    }
+    pub(crate) fn from_epoch_flags(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        let names = EpochFlag::ALL.map(|flag| flag.name());
+        let flags =
+            parse_list(&items, Expectation::EpochFlag).map_err(|e| e.with_suggestion(&names))?;
+        Ok(Self::EpochFlagItem(flags))
+    }
+    pub(crate) fn from_orbits(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        Ok(Self::OrbitItem(parse_list(
+            &items,
+            Expectation::OrbitClass,
+        )?))
+    }
+    pub(crate) fn from_blocks(content: &str) -> Result<Self, ItemError> {
+        let items = list_tokens(content);
+        Ok(Self::BlockItem(parse_list_with(
+            &items,
+            Expectation::Block,
+            |item| parse_block(item).ok(),
+        )?))
+    }
+    pub(crate) fn from_elevation_range(content: &str) -> Result<Self, ItemError> {
+        match Interval::<f64>::from_str(content) {
+            Ok(interval) if interval.within(0.0, 90.0) => Ok(Self::ElevationRangeItem(interval)),
+            _ => Err(ItemError::InvalidElevationAngle),
+        }
+    }
+    pub(crate) fn from_azimuth_range(content: &str) -> Result<Self, ItemError> {
+        match Interval::<f64>::from_str(content) {
+            Ok(interval) if interval.within(0.0, 360.0) => Ok(Self::AzimuthRangeItem(interval)),
+            _ => Err(ItemError::InvalidAzimuthAngle),
+        }
+    }
+    pub(crate) fn from_snr_range(content: &str) -> Result<Self, ItemError> {
+        match Interval::<f64>::from_str(content) {
+            Ok(interval) => Ok(Self::SNRRangeItem(interval)),
+            _ => Err(ItemError::InvalidSNR),
+        }
+    }
+    /// Returns true if this [FilterItem] describes an [Interval]
+    pub fn is_range(&self) -> bool {
+        matches!(
+            self,
+            Self::EpochRangeItem(_)
+                | Self::RelativeSpanItem(_)
+                | Self::SNRRangeItem(_)
+                | Self::ElevationRangeItem(_)
+                | Self::AzimuthRangeItem(_)
+                | Self::NavRangeItem(_)
+                | Self::RegionItem(_)
+        )
+    }
 }
 
 // use itertools::Itertools;
@@ -153,41 +865,167 @@ impl std::str::FromStr for FilterItem {
          * Type guessing
          */
         let c = content.trim();
-        let items: Vec<&str> = c.split(',').collect();
+        let items = list_tokens(content);
         /*
-         * Epoch and Durations
+         * Epoch interval
          */
-        if let Ok(start) = Epoch::from_str(items[0].trim()) {
-            if items.len() == 1 {
-                Ok(Self::EpochItem(start))
-            } else if items.len() == 2 {
-                if let Ok(end) = Epoch::from_str(items[1].trim()) {
-                    Ok(Self::DurationItem(end - start))
-                } else {
-                    Err(ItemError::InvalidEpoch)
-                }
-            } else {
-                Err(ItemError::InvalidDuration)
-            }
+        if Interval::<Epoch>::matches(c) {
+            let interval = Interval::<Epoch>::from_str(c).map_err(|_| {
+                let offset = content.len() - content.trim_start().len();
+                Diagnostic::new(offset, c, Expectation::Interval)
+            })?;
+            return Ok(Self::EpochRangeItem(interval));
+        }
         /*
-         * SV
+         * Epochs relative to the dataset
          */
-        } else if SV::from_str(items[0].trim()).is_ok() {
-            //TODO improve this:
-            // do not test 1st entry only but all possible content
-            Ok(Self::SvItem(parse_sv_list(items)?))
+        if is_relative(c) {
+            let offset = content.len() - content.trim_start().len();
+            let diagnostic = || Diagnostic::new(offset, c, Expectation::RelativeEpoch);
+            return match RelativeSpan::from_str(c) {
+                Ok(span) => Ok(Self::RelativeSpanItem(span)),
+                Err(_) if c.contains(':') => Err(diagnostic().into()),
+                Err(_) => RelativeEpoch::from_str(c)
+                    .map(Self::RelativeEpochItem)
+                    .map_err(|_| diagnostic().into()),
+            };
+        }
         /*
-         * GNSS Constellation
+         * Classify every token
          */
-        } else if Constellation::from_str(items[0].trim()).is_ok() {
-            //TODO improve this:
-            // do not test 1st entry only but all possible content
-            Ok(Self::ConstellationItem(parse_gnss_list(items)?))
-        } else {
-            // define this item a "complex"
-            Ok(Self::ComplexItem(
-                items.iter().map(|s| s.to_string()).collect(),
-            ))
+        // `C*` and `S*` are always observable patterns here,
+        // BeiDou and SBAS wildcards require the `sv` target
+        let mut kinds = items
+            .iter()
+            .map(|(_, item)| TokenKind::classify(item))
+            .collect::<Vec<_>>();
+
+        // RINEX 2 observables like `C1` or `S2` also describe satellites:
+        // lists of observables, where one token at least is not a satellite,
+        // are observable lists. `C1,C2` requires the `obs` target.
+        if kinds.iter().any(|kind| *kind != TokenKind::SV)
+            && items
+                .iter()
+                .all(|(_, item)| Observable::from_str(item).is_ok())
+        {
+            kinds.fill(TokenKind::Observable);
+        }
+
+        if kinds.iter().all(|kind| *kind == TokenKind::Complex) {
+            /*
+             * Clock
+             */
+            if c.eq_ignore_ascii_case("clk") || c.eq_ignore_ascii_case("clock") {
+                return Ok(Self::ClockItem);
+            }
+            /*
+             * Duration: a unit is required
+             */
+            if let Some(dt) = parse_duration_payload(c) {
+                return Ok(Self::DurationItem(dt));
+            }
+        }
+
+        // Carriers may share their name with other items (like E1 or L1):
+        // only lists of carrier only names are carrier lists. Mixed lists
+        // (like `L5,E5a`) are ambiguous and require the `carrier` target.
+        if kinds.iter().all(|kind| *kind == TokenKind::Carrier) {
+            return Ok(Self::CarrierItem(parse_carrier_list(&items)?));
+        }
+
+        // Tokens that are very close to a constellation name are most likely
+        // typos, rather than complex items: report them.
+        for ((offset, item), kind) in items.iter().zip(kinds.iter()) {
+            if *kind == TokenKind::Complex && suggest(item, CONSTELLATIONS).is_some() {
+                return Err(Diagnostic::new(*offset, item, Expectation::Constellation)
+                    .with_suggestion(CONSTELLATIONS)
+                    .into());
+            }
+        }
+
+        // The list family is defined by the first token that is not a constellation,
+        // every token should then be compatible with it.
+        let family = kinds
+            .iter()
+            .find(|kind| **kind != TokenKind::Constellation)
+            .copied()
+            .unwrap_or(TokenKind::Constellation);
+
+        for ((offset, item), kind) in items.iter().zip(kinds.iter()) {
+            if !family.accepts(*kind) {
+                return Err(Diagnostic::new(
+                    *offset,
+                    item,
+                    Expectation::Homogeneous(family.expected()),
+                )
+                .into());
+            }
+        }
+
+        match family {
+            /*
+             * Epoch and Durations
+             */
+            TokenKind::Epoch => {
+                let epochs = items
+                    .iter()
+                    .filter_map(|(_, item)| Epoch::from_str(item).ok())
+                    .collect::<Vec<_>>();
+                match epochs.as_slice() {
+                    [epoch] => Ok(Self::EpochItem(*epoch)),
+                    [start, end] => Ok(Self::DurationItem(*end - *start)),
+                    _ => Err(ItemError::InvalidDuration),
+                }
+            }
+            /*
+             * Observables
+             */
+            TokenKind::Observable => Ok(Self::ObservableItem(
+                items
+                    .iter()
+                    .filter_map(|(_, item)| Observable::from_str(item).ok())
+                    .collect(),
+            )),
+            /*
+             * Complex items, possibly scoped by constellations
+             */
+            TokenKind::Complex => Ok(Self::ComplexItem(
+                items.iter().map(|(_, s)| s.to_string()).collect(),
+            )),
+            /*
+             * SV and Constellations selection
+             */
+            _ if kinds.contains(&TokenKind::SvSet) => Ok(Self::SvItem(parse_sv_set_list(&items)?)),
+            _ => {
+                let (svs, constellations): (Vec<_>, Vec<_>) = items
+                    .iter()
+                    .zip(kinds.iter())
+                    .partition(|(_, kind)| **kind == TokenKind::SV);
+
+                let svs = parse_list(
+                    &svs.into_iter().map(|(item, _)| *item).collect::<Vec<_>>(),
+                    Expectation::SV,
+                )?;
+                let constellations = parse_list(
+                    &constellations
+                        .into_iter()
+                        .map(|(item, _)| *item)
+                        .collect::<Vec<_>>(),
+                    Expectation::Constellation,
+                )
+                .map_err(|e| e.with_suggestion(CONSTELLATIONS))?;
+
+                if constellations.is_empty() {
+                    Ok(Self::SvItem(svs))
+                } else if svs.is_empty() {
+                    Ok(Self::ConstellationItem(constellations))
+                } else {
+                    Ok(Self::SelectionItem {
+                        constellations,
+                        svs,
+                    })
+                }
+            }
         }
     }
 }
@@ -228,16 +1066,104 @@ impl From<Vec<Constellation>> for FilterItem {
     }
 }
 
+impl From<Observable> for FilterItem {
+    fn from(observable: Observable) -> Self {
+        Self::ObservableItem(vec![observable])
+    }
+}
+
+impl From<Vec<Observable>> for FilterItem {
+    fn from(observables: Vec<Observable>) -> Self {
+        Self::ObservableItem(observables)
+    }
+}
+
 impl std::fmt::Display for FilterItem {
+    /// Formats this [FilterItem] so it may be parsed back with [FilterItem::from_str].
+    /// Float items (elevation, azimuth, SNR) are only printed as numbers or intervals,
+    /// because their type is described by the [MaskFilter](crate::MaskFilter) prefix.
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::ConstellationItem(gnss) => {
-                write!(f, "gnss: {:?}", gnss)
+            Self::EpochItem(epoch) => write!(f, "{}", epoch),
+            Self::DurationItem(dt) => write!(f, "{}", dt),
+            Self::SNRItem(snr) => write!(f, "{}", snr),
+            Self::SNRTemplateItem(template) => write!(f, "{}", template),
+            Self::ElevationItem(elev) => write!(f, "{}", elev),
+            Self::AzimuthItem(azim) => write!(f, "{}", azim),
+            Self::FrequencyItem(freq) => write!(f, "{}", freq),
+            Self::TimeOfDayItem(tod) => write!(f, "{}", tod),
+            Self::DayOfWeekItem(days) => write!(f, "{}", days),
+            Self::DayOfYearItem(doy) => write!(f, "{}", doy),
+            Self::TimeOfWeekItem(tow) => write!(f, "{}", tow),
+            Self::RegionItem(region) => write!(f, "{}", region),
+            Self::RelativeEpochItem(epoch) => write!(f, "{}", epoch),
+            Self::RelativeSpanItem(span) => write!(f, "{}", span),
+            Self::NavItem(nav) => write!(f, "{}", nav),
+            Self::NavRangeItem(nav) => write!(f, "{}", nav),
+            Self::LliItem(lli) => write!(f, "{}", lli),
+            Self::SsiItem(ssi) => write!(f, "{}", ssi),
+            Self::EpochFlagItem(flags) => {
+                let flags = flags.iter().map(|flag| flag.name()).collect::<Vec<_>>();
+                write!(f, "{}", flags.join(","))
             }
+            Self::EpochRangeItem(interval) => write!(f, "{}", interval),
+            Self::SNRRangeItem(interval)
+            | Self::ElevationRangeItem(interval)
+            | Self::AzimuthRangeItem(interval) => write!(f, "{}", interval),
             Self::SvItem(svs) => {
-                write!(f, "sv: {:?}", svs)
+                let svs = svs.iter().map(|sv| sv.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", svs.join(","))
+            }
+            Self::ConstellationItem(gnss) => {
+                let gnss = gnss.iter().map(|c| format!("{:X}", c)).collect::<Vec<_>>();
+                write!(f, "{}", gnss.join(","))
+            }
+            Self::SelectionItem {
+                constellations,
+                svs,
+            } => {
+                let items = constellations
+                    .iter()
+                    .map(|c| format!("{:X}", c))
+                    .chain(svs.iter().map(|sv| sv.to_string()))
+                    .collect::<Vec<_>>();
+                write!(f, "{}", items.join(","))
+            }
+            Self::CarrierItem(carriers) => {
+                let carriers = carriers.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", carriers.join(","))
+            }
+            Self::MarkerItem(markers) => write!(f, "{}", markers.join(",")),
+            Self::DomesItem(domes) => {
+                let domes = domes.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", domes.join(","))
+            }
+            Self::CosparItem(cospar) => {
+                let cospar = cospar.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", cospar.join(","))
+            }
+            Self::SbasItem(services) => {
+                let services = services
+                    .iter()
+                    .map(|c| format!("{:X}", c))
+                    .collect::<Vec<_>>();
+                write!(f, "{}", services.join(","))
+            }
+            Self::OrbitItem(orbits) => {
+                let orbits = orbits.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", orbits.join(","))
             }
-            _ => Ok(()),
+            Self::BlockItem(blocks) => write!(f, "{}", blocks.join(",")),
+            Self::ObservableItem(observables) => {
+                let observables = observables
+                    .iter()
+                    .map(|o| o.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "{}", observables.join(","))
+            }
+            Self::ClockItem => write!(f, "clk"),
+            Self::ClockValueItem(clock) => write!(f, "{}", clock),
+            Self::ComplexItem(items) => write!(f, "{}", items.join(",")),
         }
     }
 }
@@ -272,12 +1198,172 @@ mod test {
         assert_eq!(target, FilterItem::DurationItem(dt));
     }
     #[test]
+    fn item_type_guessing() {
+        let g08 = SV::from_str("G08").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        assert_eq!(
+            FilterItem::from_str("G08,GPS").unwrap(),
+            FilterItem::SelectionItem {
+                constellations: vec![Constellation::GPS],
+                svs: vec![g08],
+            }
+        );
+        assert_eq!(
+            FilterItem::from_str("GAL, G08, BDS, E05").unwrap(),
+            FilterItem::SelectionItem {
+                constellations: vec![Constellation::Galileo, Constellation::BeiDou],
+                svs: vec![g08, e05],
+            }
+        );
+        assert_eq!(
+            FilterItem::from_str("iode,crs,gps").unwrap(),
+            FilterItem::ComplexItem(vec!["iode".into(), "crs".into(), "gps".into()])
+        );
+        assert_eq!(
+            FilterItem::from_str("L1C, c1c, L*").unwrap(),
+            FilterItem::ObservableItem(vec![
+                Observable::from_str("L1C").unwrap(),
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("L*").unwrap(),
+            ])
+        );
+        assert_eq!(
+            FilterItem::from_str("L1,C1,P2,S1").unwrap(),
+            FilterItem::ObservableItem(
+                ["L1", "C1", "P2", "S1"]
+                    .iter()
+                    .map(|obs| Observable::from_str(obs).unwrap())
+                    .collect()
+            )
+        );
+        assert_eq!(
+            FilterItem::from_str("C1,C2").unwrap(),
+            FilterItem::SvItem(vec![
+                SV::from_str("C01").unwrap(),
+                SV::from_str("C02").unwrap(),
+            ])
+        );
+        assert_eq!(
+            FilterItem::from_str("2020-01-14T00:00:00 UTC, 2020-01-14T01:00:00 UTC").unwrap(),
+            FilterItem::DurationItem(Duration::from_str("1 h").unwrap())
+        );
+
+        assert_eq!(
+            FilterItem::from_str("E5a, b2A").unwrap(),
+            FilterItem::CarrierItem(vec![Carrier::E5a, Carrier::B2a])
+        );
+        assert_eq!(
+            FilterItem::from_str("E1").unwrap(),
+            FilterItem::SvItem(vec![SV::from_str("E01").unwrap()])
+        );
+
+        let gps = |prn: u8| SV::new(Constellation::GPS, prn);
+        for (desc, expected) in [
+            ("G01-G04", vec![gps(1), gps(2), gps(3), gps(4)]),
+            ("G01-G04,!G03", vec![gps(1), gps(2), gps(4)]),
+            ("G30-G32, G08", vec![gps(30), gps(31), gps(32), gps(8)]),
+            ("GPS, !G02-G32", vec![gps(1)]),
+            (
+                "R01-R24,!R12",
+                (1..=24)
+                    .filter(|prn| *prn != 12)
+                    .map(|prn| SV::new(Constellation::Glonass, prn))
+                    .collect(),
+            ),
+            (
+                "E*",
+                (1..=36)
+                    .map(|prn| SV::new(Constellation::Galileo, prn))
+                    .collect(),
+            ),
+            (
+                "C19-C46",
+                (19..=46)
+                    .map(|prn| SV::new(Constellation::BeiDou, prn))
+                    .collect(),
+            ),
+        ] {
+            assert_eq!(
+                FilterItem::from_str(desc).unwrap(),
+                FilterItem::SvItem(expected),
+                "for \"{}\"",
+                desc
+            );
+        }
+        assert_eq!(
+            FilterItem::from_str("C*").unwrap(),
+            FilterItem::ObservableItem(vec![Observable::from_str("C*").unwrap()])
+        );
+
+        for (desc, span) in [
+            ("G01-G33", 0..7),
+            ("G10-G01", 0..7),
+            ("G01-E05", 0..7),
+            ("G01, !R00", 5..9),
+            ("!G01", 0..4),
+        ] {
+            let err = FilterItem::from_str(desc).unwrap_err();
+            let diagnostic = err.diagnostic().unwrap();
+            assert_eq!(diagnostic.span, span, "invalid span for \"{}\"", desc);
+            assert_eq!(diagnostic.expected, Expectation::SvSet, "for \"{}\"", desc);
+        }
+
+        for (desc, span, expected) in [
+            ("E5a, G08", 5..8, "carrier"),
+            ("E1,E5,E5a", 6..9, "satellite or constellation"),
+            ("L5, E5a", 4..7, "observable"),
+            ("E5a, L5", 5..7, "carrier"),
+            ("L1C,G08", 4..7, "observable"),
+            ("GPS, G08, L1C", 10..13, "satellite or constellation"),
+            ("C*, !C01-C62", 4..12, "observable"),
+            ("GPS, L1C, G08", 0..3, "observable"),
+            ("GPS, iode, G08", 11..14, "complex item or constellation"),
+            ("L1C, iode", 5..9, "observable"),
+            ("2020-01-14T00:00:00 UTC, G08", 25..28, "epoch"),
+        ] {
+            let err = FilterItem::from_str(desc).unwrap_err();
+            let diagnostic = err.diagnostic().unwrap();
+            assert_eq!(diagnostic.span, span, "invalid span for \"{}\"", desc);
+            assert_eq!(
+                diagnostic.expected,
+                Expectation::Homogeneous(expected),
+                "for \"{}\"",
+                desc
+            );
+        }
+    }
+    #[test]
+    fn item_diagnostics() {
+        let err = FilterItem::from_str(" GPS,GAL, Gallileo").unwrap_err();
+        let diagnostic = err.diagnostic().unwrap();
+        assert_eq!(diagnostic.span, 10..18);
+        assert_eq!(diagnostic.expected, Expectation::Constellation);
+        assert_eq!(diagnostic.suggestion.as_deref(), Some("Galileo"));
+
+        let err = FilterItem::from_str("G08,G8X").unwrap_err();
+        let diagnostic = err.diagnostic().unwrap();
+        assert_eq!(diagnostic.span, 4..7);
+        assert_eq!(
+            diagnostic.expected,
+            Expectation::Homogeneous("satellite or constellation")
+        );
+
+        let err = FilterItem::from_str("iode,GSP").unwrap_err();
+        assert_eq!(err.diagnostic().unwrap().span, 5..8);
+    }
+    #[test]
     fn test_from_elevation() {
         let desc = "90";
         assert!(
             FilterItem::from_elevation(desc).is_ok(),
             "Failed to parse Elevation Target Item"
         );
+        assert_eq!(
+            FilterItem::from_elevation("45.5").unwrap(),
+            FilterItem::ElevationItem(45.5)
+        );
+        assert!(FilterItem::from_elevation("91").is_err());
     }
     #[test]
     fn test_from_azimuth() {
@@ -288,6 +1374,23 @@ mod test {
         );
     }
     #[test]
+    fn test_from_frequency() {
+        for (desc, expected) in [
+            ("1500", 1500.0),
+            ("1500MHz", 1500.0),
+            (" 1.57542 GHz", 1575.42),
+            ("1176450 kHz", 1176.45),
+        ] {
+            match FilterItem::from_frequency(desc) {
+                Ok(FilterItem::FrequencyItem(freq)) => assert!((freq - expected).abs() < 1E-6),
+                other => panic!("unexpected result for \"{}\": {:?}", desc, other),
+            }
+        }
+        for desc in ["-10", "1500 MHZZ", "MHz"] {
+            assert!(FilterItem::from_frequency(desc).is_err());
+        }
+    }
+    #[test]
     fn test_from_snr() {
         let desc = " 12.34  ";
         assert!(
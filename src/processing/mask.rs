@@ -1,6 +1,16 @@
-use crate::processing::{FilterItem, ItemError};
+use crate::processing::{
+    Census, ClockQuantity, ClockValue, Diagnostic, DiagnosticExpectation as Expectation,
+    FilterExpr, FilterItem, Interval, ItemError, LLI_MAX, MaskReport, NavField, NavRange, NavValue,
+    SSI_MAX,
+};
+use gnss_rs::prelude::SV;
+use hifitime::Epoch;
+use std::str::FromStr;
 use thiserror::Error;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Mask filter parsing errors
 #[derive(Error, Debug)]
 pub enum Error {
@@ -10,10 +20,37 @@ pub enum Error {
     MissingOperand,
     #[error("invalid mask operand")]
     InvalidOperand,
+    #[deprecated(note = "unknown targets are reported by a Diagnostic")]
     #[error("invalid mask target \"{0}\"")]
     NonSupportedTarget(String),
+    #[deprecated(note = "invalid descriptions are reported by a Diagnostic")]
     #[error("invalid mask description")]
     InvalidDescriptor,
+    #[error("{0}")]
+    Diagnostic(#[from] Diagnostic),
+}
+
+impl Error {
+    /// Returns the [Diagnostic] attached to this error, if any.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            Self::Diagnostic(diagnostic) => Some(diagnostic),
+            Self::InvalidMaskitem(e) => e.diagnostic(),
+            _ => None,
+        }
+    }
+
+    /// Shifts the attached [Diagnostic], if any, when the description
+    /// was parsed from a portion of a larger description.
+    pub(crate) fn shifted(self, offset: usize) -> Self {
+        match self {
+            Self::Diagnostic(diagnostic) => Self::Diagnostic(diagnostic.shifted(offset)),
+            Self::InvalidMaskitem(ItemError::Diagnostic(diagnostic)) => {
+                Self::Diagnostic(diagnostic.shifted(offset))
+            }
+            e => e,
+        }
+    }
 }
 
 /// Masking trait, to retain specific GNSS data subsets.  
@@ -23,24 +60,56 @@ pub trait Masking {
     fn mask_mut(&mut self, mask: &MaskFilter);
     /// Apply [MaskFilter] to immutable self.
     fn mask(&self, mask: &MaskFilter) -> Self;
+    /// Apply [MaskFilter] to mutable self, and report what it removed.
+    fn mask_mut_with_report(&mut self, mask: &MaskFilter) -> MaskReport
+    where
+        Self: Census,
+    {
+        let before = self.census();
+        self.mask_mut(mask);
+        MaskReport::new(mask, before, self.census())
+    }
+    /// Apply [MaskFilter] to immutable self, and report what it removed.
+    fn mask_with_report(&self, mask: &MaskFilter) -> (Self, MaskReport)
+    where
+        Self: Census + Sized,
+    {
+        let masked = self.mask(mask);
+        let report = MaskReport::new(mask, self.census(), masked.census());
+        (masked, report)
+    }
 }
 
 /// MaskOperand describes how to apply a given mask
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MaskOperand {
     /// Greater than, is symbolized by ">".
+    #[cfg_attr(feature = "serde", serde(rename = ">"))]
     GreaterThan,
     /// Greater Equals, symbolized by ">=".
+    #[cfg_attr(feature = "serde", serde(rename = ">="))]
     GreaterEquals,
     /// Lower than, symbolized by "<"."
+    #[cfg_attr(feature = "serde", serde(rename = "<"))]
     LowerThan,
     /// Lower Equals, symbolized by "<=".
+    #[cfg_attr(feature = "serde", serde(rename = "<="))]
     LowerEquals,
     /// Equals, symbolized by "=".
     /// Equals operand is implied anytime the operand is omitted in the description.
+    #[cfg_attr(feature = "serde", serde(rename = "="))]
     Equals,
     /// Not Equals, symbolized by "!=".
+    #[cfg_attr(feature = "serde", serde(rename = "!="))]
     NotEquals,
+    /// Inside [Interval](crate::processing::Interval), symbolized by "in".
+    /// Inside operand is implied anytime an interval is described without operand.
+    #[cfg_attr(feature = "serde", serde(rename = "in"))]
+    Inside,
+    /// Outside [Interval](crate::processing::Interval), symbolized by "!in".
+    #[cfg_attr(feature = "serde", serde(rename = "!in"))]
+    Outside,
 }
 
 impl std::str::FromStr for MaskOperand {
@@ -59,17 +128,64 @@ impl std::str::FromStr for MaskOperand {
             Ok(Self::Equals)
         } else if c.starts_with("!=") {
             Ok(Self::NotEquals)
+        } else if is_keyword(c, "!in") {
+            Ok(Self::Outside)
+        } else if is_keyword(c, "in") {
+            Ok(Self::Inside)
         } else {
             Err(Error::InvalidOperand)
         }
     }
 }
 
+/// Returns true if content starts with given keyword, as an entire word
+fn is_keyword(content: &str, keyword: &str) -> bool {
+    content
+        .get(..keyword.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(keyword))
+        && !content[keyword.len()..].starts_with(|c: char| c.is_alphanumeric())
+}
+
+impl std::fmt::Display for MaskOperand {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::GreaterThan => write!(f, ">"),
+            Self::GreaterEquals => write!(f, ">="),
+            Self::LowerThan => write!(f, "<"),
+            Self::LowerEquals => write!(f, "<="),
+            Self::Equals => write!(f, "="),
+            Self::NotEquals => write!(f, "!="),
+            Self::Inside => write!(f, "in"),
+            Self::Outside => write!(f, "!in"),
+        }
+    }
+}
+
 impl MaskOperand {
     pub(crate) const fn formatted_len(&self) -> usize {
         match &self {
             Self::Equals | Self::GreaterThan | Self::LowerThan => 1,
-            Self::NotEquals | Self::LowerEquals | Self::GreaterEquals => 2,
+            Self::NotEquals | Self::LowerEquals | Self::GreaterEquals | Self::Inside => 2,
+            Self::Outside => 3,
+        }
+    }
+    /// Returns true if this [MaskOperand] applies to [Interval](crate::processing::Interval)s
+    pub fn is_range(&self) -> bool {
+        matches!(self, Self::Inside | Self::Outside)
+    }
+    /// Returns the logical complement of this [MaskOperand]: the operand that
+    /// retains exactly what this one discards (like `>` and `<=`).
+    /// Unlike [std::ops::Not], which mirrors the comparison (like `>` and `<`).
+    pub fn complement(&self) -> Self {
+        match self {
+            Self::Equals => Self::NotEquals,
+            Self::NotEquals => Self::Equals,
+            Self::GreaterThan => Self::LowerEquals,
+            Self::GreaterEquals => Self::LowerThan,
+            Self::LowerThan => Self::GreaterEquals,
+            Self::LowerEquals => Self::GreaterThan,
+            Self::Inside => Self::Outside,
+            Self::Outside => Self::Inside,
         }
     }
 }
@@ -84,12 +200,15 @@ impl std::ops::Not for MaskOperand {
             Self::GreaterThan => Self::LowerThan,
             Self::LowerThan => Self::GreaterThan,
             Self::LowerEquals => Self::GreaterEquals,
+            Self::Inside => Self::Outside,
+            Self::Outside => Self::Inside,
         }
     }
 }
 
 /// Apply MaskFilters to focus on datasubsets you're interested in.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MaskFilter {
     /// Item describes what subset we this [MaskFilter] applies to.
     pub item: FilterItem,
@@ -107,132 +226,491 @@ impl std::ops::Not for MaskFilter {
     }
 }
 
+impl MaskFilter {
+    /// Returns the logical complement of this [MaskFilter],
+    /// that retains exactly what this [MaskFilter] discards.
+    pub fn complement(&self) -> Self {
+        Self {
+            operand: self.operand.complement(),
+            item: self.item.clone(),
+        }
+    }
+}
+
 impl std::ops::BitOr for MaskFilter {
-    type Output = Self;
-    fn bitor(self, rhs: Self) -> Self {
-        if self.operand == rhs.operand {
-            Self {
-                operand: self.operand,
-                item: self.item | rhs.item,
+    type Output = FilterExpr;
+    /// Combines two [MaskFilter]s into a [FilterExpr::Or],
+    /// that retains what either [MaskFilter] retains.
+    fn bitor(self, rhs: Self) -> FilterExpr {
+        FilterExpr::from(self) | FilterExpr::from(rhs)
+    }
+}
+
+impl std::fmt::Display for MaskFilter {
+    /// Formats this [MaskFilter] so it may be parsed back with [MaskFilter::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let target = match &self.item {
+            FilterItem::NavItem(nav) => nav.field.name(),
+            FilterItem::NavRangeItem(nav) => nav.field.name(),
+            FilterItem::ClockValueItem(clock) => clock.quantity.name(),
+            FilterItem::ElevationItem(_) | FilterItem::ElevationRangeItem(_) => "e",
+            FilterItem::AzimuthItem(_) | FilterItem::AzimuthRangeItem(_) => "a",
+            FilterItem::SNRItem(_)
+            | FilterItem::SNRRangeItem(_)
+            | FilterItem::SNRTemplateItem(_) => "snr",
+            FilterItem::FrequencyItem(_) => "freq",
+            FilterItem::CarrierItem(_) => "carrier",
+            FilterItem::TimeOfDayItem(_) => "tod",
+            FilterItem::DayOfWeekItem(_) => "dow",
+            FilterItem::DayOfYearItem(_) => "doy",
+            FilterItem::TimeOfWeekItem(_) => "tow",
+            FilterItem::RegionItem(_) => "region",
+            FilterItem::MarkerItem(_) => "marker",
+            FilterItem::DomesItem(_) => "domes",
+            FilterItem::CosparItem(_) => "cospar",
+            FilterItem::SbasItem(_) => "sbas",
+            FilterItem::LliItem(_) => "lli",
+            FilterItem::SsiItem(_) => "ssi",
+            FilterItem::EpochFlagItem(_) => "epochflag",
+            FilterItem::OrbitItem(_) => "orbit",
+            FilterItem::BlockItem(_) => "block",
+            // RINEX 2 observables that also describe satellites, like `C1`
+            FilterItem::ObservableItem(observables)
+                if observables
+                    .iter()
+                    .all(|obs| SV::from_str(&obs.to_string()).is_ok()) =>
+            {
+                "obs"
             }
+            _ => "",
+        };
+        if self.operand.is_range() {
+            // keyword operands need to be delimited
+            let target = if target.is_empty() {
+                String::new()
+            } else {
+                format!("{} ", target)
+            };
+            write!(f, "{}{} {}", target, self.operand, self.item)
         } else {
-            // not permitted on operand mismatch
-            self.clone()
+            write!(f, "{}{}{}", target, self.operand, self.item)
         }
     }
 }
 
-impl std::ops::BitOrAssign for MaskFilter {
-    fn bitor_assign(&mut self, rhs: Self) {
-        self.item = self.item.clone() | rhs.item;
+/// Mask targets that must be described prior the operand,
+/// because their type cannot be guessed from the payload.
+const TARGETS: &[&str] = &[
+    "e",
+    "elev",
+    "elevation",
+    "a",
+    "azim",
+    "azimuth",
+    "snr",
+    "freq",
+    "frequency",
+    "carrier",
+    "sv",
+    "obs",
+    "tod",
+    "dow",
+    "week",
+    "doy",
+    "tow",
+    "mjd",
+    "region",
+    "marker",
+    "domes",
+    "cospar",
+    "sbas",
+    "lli",
+    "ssi",
+    "epochflag",
+    "orbit",
+    "block",
+];
+
+/// Parses the list payload of a mask target
+type ListParser = fn(&str) -> Result<FilterItem, ItemError>;
+
+/// Mask targets that describe lists, with their parser and the expected list items.
+/// Lists do not support intervals.
+const LIST_TARGETS: &[(&str, ListParser, Expectation)] = &[
+    ("carrier", FilterItem::from_carriers, Expectation::Carrier),
+    ("sv", FilterItem::from_sv_sets, Expectation::SvSet),
+    ("obs", FilterItem::from_observables, Expectation::Observable),
+    ("marker", FilterItem::from_markers, Expectation::Marker),
+    ("domes", FilterItem::from_domes, Expectation::DOMES),
+    ("cospar", FilterItem::from_cospar, Expectation::COSPAR),
+    ("sbas", FilterItem::from_sbas, Expectation::SBAS),
+    (
+        "epochflag",
+        FilterItem::from_epoch_flags,
+        Expectation::EpochFlag,
+    ),
+    ("orbit", FilterItem::from_orbits, Expectation::OrbitClass),
+    ("block", FilterItem::from_blocks, Expectation::Block),
+];
+
+/// Mask targets that describe calendar periods, see [MaskFilter::over_period]
+const PERIODS: &[&str] = &["week", "doy", "mjd"];
+
+/// Returns true if this payload describes a [SNRTemplate](crate::processing::SNRTemplate)
+fn is_snr_template(value: &str) -> bool {
+    let lowercase = value.to_ascii_lowercase();
+    lowercase.starts_with("template(") || lowercase.starts_with("table(")
+}
+
+/// Parses the [FilterItem] of a mask whose target is described prior the operand.
+/// `payload_offset` is the byte offset of the payload, relative to the target.
+/// An interval payload is expected when `ranged` is true.
+fn parse_targeted_item(
+    target: &str,
+    payload: &str,
+    payload_offset: usize,
+    ranged: bool,
+) -> Result<FilterItem, Error> {
+    let value = payload.trim();
+    let value_offset = payload_offset + payload.len() - payload.trim_start().len();
+
+    // targets that do not support intervals
+    let scalar = |parsed: fn(&str) -> Result<FilterItem, ItemError>| {
+        if ranged {
+            Err(ItemError::UnknownItem(value.to_string()))
+        } else {
+            parsed(value)
+        }
+    };
+
+    if let Ok(field) = NavField::from_str(target) {
+        let (parsed, expected) = if ranged {
+            (
+                NavRange::parse(field, value).map(FilterItem::NavRangeItem),
+                Expectation::Interval,
+            )
+        } else if field.is_integer() {
+            (
+                NavValue::parse(field, value).map(FilterItem::NavItem),
+                Expectation::Integer,
+            )
+        } else {
+            (
+                NavValue::parse(field, value).map(FilterItem::NavItem),
+                Expectation::Float,
+            )
+        };
+        return parsed.map_err(|_| Diagnostic::new(value_offset, value, expected).into());
+    }
+
+    if let Ok(quantity) = ClockQuantity::from_str(target) {
+        return match ClockValue::parse(quantity, value) {
+            Ok(clock) if !ranged => Ok(FilterItem::ClockValueItem(clock)),
+            _ => Err(Diagnostic::new(value_offset, value, Expectation::Clock).into()),
+        };
     }
+
+    if let Some((_, parse, expected)) = LIST_TARGETS
+        .iter()
+        .find(|(name, _, _)| target.eq_ignore_ascii_case(name))
+    {
+        if ranged {
+            return Err(Diagnostic::new(value_offset, value, expected.clone()).into());
+        }
+        // diagnostics designate the faulty list item
+        return parse(payload).map_err(|e| match e {
+            ItemError::Diagnostic(diagnostic) => diagnostic.shifted(payload_offset).into(),
+            e => e.into(),
+        });
+    }
+
+    let (parsed, expected) = match (target.to_ascii_lowercase().as_str(), ranged) {
+        ("e" | "elev" | "elevation", false) => (
+            FilterItem::from_elevation(value),
+            Expectation::FloatRange(0.0, 90.0),
+        ),
+        ("e" | "elev" | "elevation", true) => (
+            FilterItem::from_elevation_range(value),
+            Expectation::Interval,
+        ),
+        ("a" | "azim" | "azimuth", false) => (
+            FilterItem::from_azimuth(value),
+            Expectation::FloatRange(0.0, 360.0),
+        ),
+        ("a" | "azim" | "azimuth", true) => {
+            (FilterItem::from_azimuth_range(value), Expectation::Interval)
+        }
+        ("snr", false) if is_snr_template(value) => (
+            FilterItem::from_snr_template(value),
+            Expectation::SNRTemplate,
+        ),
+        ("snr", false) => (FilterItem::from_snr(value), Expectation::Float),
+        ("snr", true) => (FilterItem::from_snr_range(value), Expectation::Interval),
+        ("week", false) => (FilterItem::from_week(value), Expectation::Week),
+        ("week", true) => (FilterItem::from_week_range(value), Expectation::Week),
+        ("mjd", false) => (FilterItem::from_mjd(value), Expectation::MJD),
+        ("mjd", true) => (FilterItem::from_mjd_range(value), Expectation::MJD),
+        ("doy", _) => (scalar(FilterItem::from_doy), Expectation::DayOfYear),
+        ("tow", _) => (scalar(FilterItem::from_tow), Expectation::TimeOfWeek),
+        ("freq" | "frequency", _) => (scalar(FilterItem::from_frequency), Expectation::Frequency),
+        // regions are ranges by nature
+        ("region", _) => (FilterItem::from_region(value), Expectation::Region),
+        ("lli", _) => (
+            scalar(FilterItem::from_lli),
+            Expectation::IntegerRange(0, LLI_MAX),
+        ),
+        ("ssi", _) => (
+            scalar(FilterItem::from_ssi),
+            Expectation::IntegerRange(0, SSI_MAX),
+        ),
+        ("tod", _) => (scalar(FilterItem::from_time_of_day), Expectation::TimeOfDay),
+        ("dow", _) => (
+            scalar(FilterItem::from_days_of_week),
+            Expectation::DaysOfWeek,
+        ),
+        _ => {
+            // navigation fields and clock quantities are not listed in TARGETS,
+            // because they also describe items when used without operand
+            let candidates = TARGETS
+                .iter()
+                .copied()
+                .chain(NavField::ALL.iter().map(|field| field.name()))
+                .chain(ClockQuantity::ALL.iter().map(|quantity| quantity.name()))
+                .collect::<Vec<_>>();
+            return Err(Diagnostic::new(0, target, Expectation::Target)
+                .with_suggestion(&candidates)
+                .into());
+        }
+    };
+
+    parsed.map_err(|_| Diagnostic::new(value_offset, value, expected).into())
 }
 
-impl std::str::FromStr for MaskFilter {
-    type Err = Error;
-    fn from_str(content: &str) -> Result<Self, Self::Err> {
-        let cleanedup = content.trim_start();
-        if cleanedup.len() < 2 {
+impl MaskFilter {
+    /// Parses a [MaskFilter] from a description that is not padded with whitespaces.
+    /// Possible [Diagnostic]s are expressed relative to this description.
+    fn parse(content: &str) -> Result<Self, Error> {
+        if content.len() < 2 {
             /*
              * we're most likely unable to parsed both
              * an operand and a filter payload
              */
-            return Err(Error::InvalidDescriptor);
+            return Err(Diagnostic::new(0, content, Expectation::Item).into());
         }
 
-        let mut operand: Option<MaskOperand> = None;
-        let mut operand_offset: Option<usize> = None;
         // In some cases, the target item comes first.
         // This allows more "human readable" descriptions,
         // but makes parsing a little harder.
 
-        // Try to locate a mask operand within given content
-        for i in 0..cleanedup.len() - 1 {
-            if i < cleanedup.len() - 2 {
-                if let Ok(op) = MaskOperand::from_str(&cleanedup[i..i + 2]) {
-                    operand = Some(op.clone());
-                    operand_offset = Some(i);
-                    break;
+        // Try to locate a mask operand within given content:
+        // either a symbol, or the "in" keyword, whichever comes first.
+        let symbol = content.find(['>', '<', '=', '!']).and_then(|offset| {
+            MaskOperand::from_str(&content[offset..])
+                .ok()
+                .map(|operand| (operand, offset))
+        });
+
+        let keyword = content
+            .char_indices()
+            .filter(|(offset, _)| *offset == 0 || content[..*offset].ends_with(char::is_whitespace))
+            .find(|(offset, _)| is_keyword(&content[*offset..], "in"))
+            .map(|(offset, _)| (MaskOperand::Inside, offset));
+
+        let located = match (symbol, keyword) {
+            (Some(symbol), Some(keyword)) if keyword.1 < symbol.1 => Some(keyword),
+            (None, keyword) => keyword,
+            (symbol, _) => symbol,
+        };
+
+        match located {
+            Some((operand, operand_offset)) if operand_offset > 0 => {
+                // Some characters exist between .start() and identified operand.
+                // Type guessing for filter target will not work.
+                // This only exits for Elevation Angle, Azimuth Angle, SNR, frequency, carrier and calendar masks at the moment.
+
+                // Simply due to the fact that the operand is located
+                // after the identifier, in those cases
+                let target = content[..operand_offset].trim_end();
+                let payload_offset = operand_offset + operand.formatted_len();
+                let payload = &content[payload_offset..];
+                let ranged = operand.is_range() || Interval::<f64>::matches(payload);
+                let item = parse_targeted_item(target, payload, payload_offset, ranged)?;
+                let period = PERIODS.iter().any(|p| p.eq_ignore_ascii_case(target));
+                match item {
+                    FilterItem::EpochRangeItem(interval) if period && !ranged => {
+                        Ok(Self::over_period(operand, interval))
+                    }
+                    item => Self::checked(operand, operand_offset, item, content, payload_offset),
                 }
-            } else if let Ok(op) = MaskOperand::from_str(&cleanedup[i..i + 1]) {
-                operand = Some(op.clone());
-                operand_offset = Some(i);
-                break;
+            }
+            Some((operand, operand_offset)) => {
+                // Descriptor starts with mask operand.
+                // Filter target type guessing is possible.
+                let offset = operand_offset + operand.formatted_len();
+                let item = FilterItem::from_str(&content[offset..])
+                    .map_err(|e| Error::from(e).shifted(offset))?;
+                Self::checked(operand, operand_offset, item, content, offset)
+            }
+            None => {
+                // Operand was not found, it's either omitted and Eq() is implied,
+                // or this description is missing an operand after a mask target.
+                let word = content.split_whitespace().next().unwrap_or_default();
+                if TARGETS
+                    .iter()
+                    .any(|target| target.eq_ignore_ascii_case(word))
+                {
+                    let rest = content[word.len()..].trim_start();
+                    let offset = content.len() - rest.len();
+                    let found = rest.split_whitespace().next().unwrap_or_default();
+                    return Err(Diagnostic::new(offset, found, Expectation::Operand).into());
+                }
+                let item = FilterItem::from_str(content)?;
+                Self::checked(MaskOperand::Equals, 0, item, content, 0)
             }
         }
+    }
 
-        let operand_omitted = operand_offset.is_none();
+    /// Builds a [MaskFilter] that compares [Epoch]s to an entire period (like a week):
+    /// `week>2310` retains epochs after the end of week 2310,
+    /// while `week<=2310` retains epochs prior the end of week 2310.
+    fn over_period(operand: MaskOperand, period: Interval<Epoch>) -> Self {
+        let (operand, item) = match operand {
+            MaskOperand::GreaterThan => (
+                MaskOperand::GreaterEquals,
+                FilterItem::EpochItem(period.end),
+            ),
+            MaskOperand::GreaterEquals => (
+                MaskOperand::GreaterEquals,
+                FilterItem::EpochItem(period.start),
+            ),
+            MaskOperand::LowerThan => (MaskOperand::LowerThan, FilterItem::EpochItem(period.start)),
+            MaskOperand::LowerEquals => (MaskOperand::LowerThan, FilterItem::EpochItem(period.end)),
+            MaskOperand::Equals | MaskOperand::Inside => {
+                (MaskOperand::Inside, FilterItem::EpochRangeItem(period))
+            }
+            MaskOperand::NotEquals | MaskOperand::Outside => {
+                (MaskOperand::Outside, FilterItem::EpochRangeItem(period))
+            }
+        };
+        Self { operand, item }
+    }
 
-        let (operand, operand_offset): (MaskOperand, usize) = match operand_offset.is_some() {
-            true => (operand.unwrap(), operand_offset.unwrap()),
-            false => {
-                /*
-                 * Operand was not found, it's either omitted and Eq() is implied,
-                 * or this parser will soon fail due to faulty content
-                 */
-                (MaskOperand::Equals, 0)
+    /// Resolves the relative epochs of this [MaskFilter] within given time span,
+    /// into absolute epochs. Other [MaskFilter]s are returned as is.
+    pub fn resolved(&self, span: &Interval<Epoch>) -> Self {
+        let item = match &self.item {
+            FilterItem::RelativeEpochItem(epoch) => FilterItem::EpochItem(epoch.resolve(span)),
+            FilterItem::RelativeSpanItem(period) => {
+                FilterItem::EpochRangeItem(period.resolve(span))
             }
+            _ => return self.clone(),
         };
+        Self {
+            operand: self.operand.clone(),
+            item,
+        }
+    }
 
-        if operand_offset > 0 {
-            // Some characters exist between .start() and identified operand.
-            // Type guessing for filter target will not work.
-            // This only exits for Elevation Angle, Azimuth Angle and SNR masks at the moment.
-
-            // Simply due to the fact that the operand is located
-            // after the identifier, in those cases
-
-            let start = &cleanedup[..operand_offset];
-            if start[0..1].eq("e") {
-                // --> Elevation Mask case
-                let float_offset = operand_offset + operand.formatted_len() + 2;
-                Ok(Self {
-                    operand,
-                    item: FilterItem::from_elevation(cleanedup[float_offset..].trim())?,
-                })
-            } else if content[0..1].eq("a") {
-                // --> Azimuth Mask case
-                let float_offset = operand_offset + operand.formatted_len() + 2;
-                Ok(Self {
-                    operand,
-                    item: FilterItem::from_azimuth(cleanedup[float_offset..].trim())?,
-                })
-            } else {
-                // We're only left with SNR mask case
-                let float_offset = operand_offset + operand.formatted_len() + 2;
-                if content[0..3].eq("snr") {
-                    Ok(Self {
-                        operand,
-                        item: FilterItem::from_snr(cleanedup[float_offset..].trim())?,
-                    })
-                } else {
-                    Err(Error::NonSupportedTarget(
-                        cleanedup[..operand_offset].to_string(),
-                    ))
-                }
+    /// Returns true if this [MaskFilter] describes relative epochs,
+    /// that should be resolved prior application (see [TimeSpan](crate::processing::TimeSpan)).
+    pub fn is_relative(&self) -> bool {
+        matches!(
+            self.item,
+            FilterItem::RelativeEpochItem(_) | FilterItem::RelativeSpanItem(_)
+        )
+    }
+
+    /// Verifies the [MaskOperand] applies to this kind of [FilterItem].
+    /// Intervals are described with range operands, and equality operands
+    /// are converted to their range counterpart.
+    fn checked(
+        operand: MaskOperand,
+        operand_offset: usize,
+        item: FilterItem,
+        content: &str,
+        payload_offset: usize,
+    ) -> Result<Self, Error> {
+        let operand = match (item.is_range(), operand) {
+            (true, MaskOperand::Equals | MaskOperand::Inside) => MaskOperand::Inside,
+            (true, MaskOperand::NotEquals | MaskOperand::Outside) => MaskOperand::Outside,
+            (true, operand) => {
+                let found = &content[operand_offset..operand_offset + operand.formatted_len()];
+                return Err(Diagnostic::new(operand_offset, found, Expectation::Operand).into());
             }
-        } else {
-            // Descriptor starts with mask operand.
-            // Filter target type guessing is possible.
-            let offset: usize = match operand_omitted {
-                false => operand_offset + operand.formatted_len(),
-                true => 0,
-            };
+            (false, operand) if operand.is_range() => {
+                let payload = &content[payload_offset..];
+                let offset = payload_offset + payload.len() - payload.trim_start().len();
+                return Err(Diagnostic::new(offset, payload.trim(), Expectation::Interval).into());
+            }
+            // LLI masks test bits, they may not be ordered
+            (false, operand)
+                if matches!(item, FilterItem::LliItem(_))
+                    && !matches!(operand, MaskOperand::Equals | MaskOperand::NotEquals) =>
+            {
+                let found = &content[operand_offset..operand_offset + operand.formatted_len()];
+                return Err(Diagnostic::new(operand_offset, found, Expectation::Operand).into());
+            }
+            (false, operand) => operand,
+        };
+        Ok(Self { operand, item })
+    }
+}
 
-            Ok(Self {
-                operand,
-                item: FilterItem::from_str(cleanedup[offset..].trim_start())?,
-            })
-        }
+impl std::str::FromStr for MaskFilter {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let cleanedup = content.trim_start();
+        let offset = content.len() - cleanedup.len();
+        Self::parse(cleanedup.trim_end()).map_err(|e| e.shifted(offset))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use gnss_rs::prelude::{Constellation, SV};
-    use hifitime::Epoch;
-    use std::str::FromStr;
+    use crate::processing::{
+        Carrier, DayOfYear, DaysOfWeek, EpochFlag, Observable, OrbitClass, Region, RelativeEpoch,
+        RelativeSpan, SNRTemplate, TemplateInterpolation, TimeAnchor, TimeOfDay, TimeOfWeek,
+    };
+    use gnss_rs::prelude::{COSPAR, Constellation, DOMES, SV};
+    use hifitime::{Duration, TimeScale, Weekday};
+    use std::{ops::Range, str::FromStr};
+
+    /// Asserts that each description parses to the expected [MaskFilter],
+    /// and that it is formatted back losslessly.
+    fn assert_masks<const N: usize>(cases: [(&str, MaskOperand, FilterItem); N]) {
+        for (desc, operand, item) in cases {
+            let mask = MaskFilter::from_str(desc).unwrap();
+            assert_eq!(mask, MaskFilter { operand, item }, "for \"{}\"", desc);
+            assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+        }
+    }
+
+    /// Asserts that each description is rejected with a [Diagnostic]
+    /// designating the expected span.
+    fn assert_diagnostics(cases: &[(&str, Range<usize>, Expectation)]) {
+        for (desc, span, expected) in cases {
+            let err = MaskFilter::from_str(desc).unwrap_err();
+            let diagnostic = err.diagnostic().unwrap();
+            assert_eq!(diagnostic.span, *span, "invalid span for \"{}\"", desc);
+            assert_eq!(diagnostic.expected, *expected, "for \"{}\"", desc);
+        }
+    }
+    #[test]
+    fn mask_operand_complement() {
+        for (operand, complement) in [
+            (MaskOperand::GreaterThan, MaskOperand::LowerEquals),
+            (MaskOperand::GreaterEquals, MaskOperand::LowerThan),
+            (MaskOperand::Equals, MaskOperand::NotEquals),
+            (MaskOperand::Inside, MaskOperand::Outside),
+        ] {
+            assert_eq!(operand.complement(), complement);
+            assert_eq!(complement.complement(), operand);
+        }
+        let mask = MaskFilter::from_str("e>10").unwrap();
+        assert_eq!(mask.complement(), MaskFilter::from_str("e<=10").unwrap());
+    }
+
     #[test]
     fn mask_operand() {
         for (descriptor, opposite_desc) in [
@@ -241,6 +719,7 @@ mod test {
             ("=", "!="),
             ("<", ">"),
             ("<=", ">="),
+            ("in", "!in"),
         ] {
             let operand = MaskOperand::from_str(descriptor);
             assert!(
@@ -299,6 +778,12 @@ mod test {
                 desc
             );
         }
+
+        let mask = MaskFilter::from_str("e>10").unwrap();
+        assert_eq!(mask.item, FilterItem::ElevationItem(10.0));
+        let mask = MaskFilter::from_str("azim<=270").unwrap();
+        assert_eq!(mask.item, FilterItem::AzimuthItem(270.0));
+        assert!(MaskFilter::from_str("azim>361").is_err());
     }
     #[test]
     fn mask_gnss() {
@@ -322,6 +807,38 @@ mod test {
             assert_eq!(!mask.unwrap(), opposite.unwrap(), "{}", "MaskFilter::Not()");
         }
 
+        for desc in ["G01-G03,!G02", "!=G01-G03,!G02", "= G01 - G03, !G02"] {
+            let mask = MaskFilter::from_str(desc).unwrap();
+            assert_eq!(
+                mask.item,
+                FilterItem::SvItem(vec![
+                    SV::new(Constellation::GPS, 1),
+                    SV::new(Constellation::GPS, 3)
+                ]),
+                "for \"{}\"",
+                desc
+            );
+        }
+
+        // BeiDou and SBAS wildcards require the sv target
+        for desc in ["sv=C*,!C01-C62", "SV = C* , !C01-C62", "sv!=C63"] {
+            let mask = MaskFilter::from_str(desc).unwrap();
+            assert_eq!(
+                mask.item,
+                FilterItem::SvItem(vec![SV::new(Constellation::BeiDou, 63)]),
+                "for \"{}\"",
+                desc
+            );
+            assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+        }
+        assert_eq!(
+            MaskFilter::from_str("C*").unwrap().item,
+            FilterItem::ObservableItem(vec![Observable::from_str("C*").unwrap()])
+        );
+        let err = MaskFilter::from_str("sv=G01,!X05").unwrap_err();
+        assert_eq!(err.diagnostic().unwrap().span, 7..11);
+        assert_eq!(err.diagnostic().unwrap().expected, Expectation::SvSet);
+
         let mask = MaskFilter::from_str("=GPS,GAL,GLO").unwrap();
         assert_eq!(
             mask,
@@ -389,19 +906,712 @@ mod test {
         assert_eq!(mask, m2);
     }
     #[test]
-    fn mask_complex() {
+    fn mask_ranges() {
+        for (desc, operand, item) in [
+            (
+                "e in 10..60",
+                MaskOperand::Inside,
+                FilterItem::ElevationRangeItem(Interval::new(10.0, 60.0)),
+            ),
+            (
+                "e = 10..30",
+                MaskOperand::Inside,
+                FilterItem::ElevationRangeItem(Interval::new(10.0, 30.0)),
+            ),
+            (
+                "snr in [30,45)",
+                MaskOperand::Inside,
+                FilterItem::SNRRangeItem(Interval::new(30.0, 45.0)),
+            ),
+            (
+                "azim !in 90..=180",
+                MaskOperand::Outside,
+                FilterItem::AzimuthRangeItem(Interval::inclusive(90.0, 180.0)),
+            ),
+            (
+                "2020-01-01T00:00:00 UTC..2020-01-01T06:00:00 UTC",
+                MaskOperand::Inside,
+                FilterItem::EpochRangeItem(Interval::new(
+                    Epoch::from_str("2020-01-01T00:00:00 UTC").unwrap(),
+                    Epoch::from_str("2020-01-01T06:00:00 UTC").unwrap(),
+                )),
+            ),
+            (
+                "!= [2020-01-01T00:00:00 UTC, 2020-01-01T06:00:00 UTC]",
+                MaskOperand::Outside,
+                FilterItem::EpochRangeItem(Interval::inclusive(
+                    Epoch::from_str("2020-01-01T00:00:00 UTC").unwrap(),
+                    Epoch::from_str("2020-01-01T06:00:00 UTC").unwrap(),
+                )),
+            ),
+        ] {
+            let mask = MaskFilter::from_str(desc).unwrap();
+            assert_eq!(mask, MaskFilter { operand, item }, "for \"{}\"", desc);
+            assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+            assert_eq!((!mask.clone()).operand, !mask.operand.clone());
+        }
+
+        assert_diagnostics(&[
+            ("e in 10..100", 5..12, Expectation::Interval),
+            ("e in 10", 5..7, Expectation::Interval),
+            ("snr > 10..30", 4..5, Expectation::Operand),
+            ("in G08", 3..6, Expectation::Interval),
+        ]);
+    }
+    #[test]
+    fn mask_diagnostics() {
+        for (desc, span, expected, suggestion) in [
+            (" e >= 120", 6..9, Expectation::FloatRange(0.0, 90.0), None),
+            ("a<x", 2..3, Expectation::FloatRange(0.0, 360.0), None),
+            ("snr>", 4..4, Expectation::Float, None),
+            ("snt>30", 0..3, Expectation::Target, Some("snr")),
+            ("elv < 10", 0..3, Expectation::Target, Some("elev")),
+            ("e 10", 2..4, Expectation::Operand, None),
+            (
+                "=G08, G8X",
+                6..9,
+                Expectation::Homogeneous("satellite or constellation"),
+                None,
+            ),
+            (
+                "!= GPS, GSP",
+                8..11,
+                Expectation::Constellation,
+                Some("GPS"),
+            ),
+            ("=GAL, gsp", 6..9, Expectation::Constellation, Some("GPS")),
+            ("  G", 2..3, Expectation::Item, None),
+        ] {
+            let err = MaskFilter::from_str(desc).unwrap_err();
+            let diagnostic = err
+                .diagnostic()
+                .unwrap_or_else(|| panic!("no diagnostic for \"{}\": {}", desc, err));
+            assert_eq!(diagnostic.span, span, "invalid span for \"{}\"", desc);
+            assert_eq!(diagnostic.expected, expected, "for \"{}\"", desc);
+            assert_eq!(
+                diagnostic.suggestion.as_deref(),
+                suggestion,
+                "for \"{}\"",
+                desc
+            );
+        }
+    }
+    #[test]
+    fn mask_snr_templates() {
+        let mask = MaskFilter::from_str("snr>template(0:25,15:30,30:35)").unwrap();
+        assert_eq!(
+            mask,
+            MaskFilter {
+                operand: MaskOperand::GreaterThan,
+                item: FilterItem::SNRTemplateItem(
+                    SNRTemplate::new(
+                        vec![(0.0, 25.0), (15.0, 30.0), (30.0, 35.0)],
+                        TemplateInterpolation::Linear
+                    )
+                    .unwrap()
+                ),
+            }
+        );
+        assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+
+        let mask = MaskFilter::from_str("snr >= table(E5a, 0:20, 30:32)").unwrap();
+        assert_eq!(mask.operand, MaskOperand::GreaterEquals);
+        assert_eq!(
+            mask.item,
+            FilterItem::SNRTemplateItem(
+                SNRTemplate::new(vec![(0.0, 20.0), (30.0, 32.0)], TemplateInterpolation::Step)
+                    .unwrap()
+                    .with_carrier(Carrier::E5a)
+            )
+        );
+        assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+
+        assert_diagnostics(&[
+            ("snr>template(30:25,15:30)", 4..25, Expectation::SNRTemplate),
+            ("snr>templat(0:25)", 4..17, Expectation::Float),
+            ("snr in template(0:25)", 7..21, Expectation::Interval),
+        ]);
+    }
+
+    #[test]
+    fn mask_relative_epochs() {
+        let epoch = |desc: &str| Epoch::from_str(desc).unwrap();
+        let span = Interval::inclusive(
+            epoch("2020-01-01T00:00:00 GPST"),
+            epoch("2020-01-02T00:00:00 GPST"),
+        );
+
+        for (desc, operand, item, resolved_operand, resolved) in [
+            (
+                ">start+30min",
+                MaskOperand::GreaterThan,
+                FilterItem::RelativeEpochItem(RelativeEpoch {
+                    anchor: TimeAnchor::Start,
+                    offset: Duration::from_str("30 min").unwrap(),
+                }),
+                MaskOperand::GreaterThan,
+                FilterItem::EpochItem(epoch("2020-01-01T00:30:00 GPST")),
+            ),
+            (
+                "<= end-2h",
+                MaskOperand::LowerEquals,
+                FilterItem::RelativeEpochItem(RelativeEpoch {
+                    anchor: TimeAnchor::End,
+                    offset: -Duration::from_str("2 h").unwrap(),
+                }),
+                MaskOperand::LowerEquals,
+                FilterItem::EpochItem(epoch("2020-01-01T22:00:00 GPST")),
+            ),
+            (
+                "last:2h",
+                MaskOperand::Inside,
+                FilterItem::RelativeSpanItem(RelativeSpan::Last(
+                    Duration::from_str("2 h").unwrap(),
+                )),
+                MaskOperand::Inside,
+                FilterItem::EpochRangeItem(Interval::inclusive(
+                    epoch("2020-01-01T22:00:00 GPST"),
+                    span.end,
+                )),
+            ),
+            (
+                "!=first:30min",
+                MaskOperand::Outside,
+                FilterItem::RelativeSpanItem(RelativeSpan::First(
+                    Duration::from_str("30 min").unwrap(),
+                )),
+                MaskOperand::Outside,
+                FilterItem::EpochRangeItem(Interval::inclusive(
+                    span.start,
+                    epoch("2020-01-01T00:30:00 GPST"),
+                )),
+            ),
+        ] {
+            let mask = MaskFilter::from_str(desc).unwrap();
+            assert_eq!(mask, MaskFilter { operand, item }, "for \"{}\"", desc);
+            assert!(mask.is_relative());
+            assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+
+            let resolved_mask = mask.resolved(&span);
+            assert_eq!(
+                resolved_mask,
+                MaskFilter {
+                    operand: resolved_operand,
+                    item: resolved,
+                },
+                "for \"{}\"",
+                desc
+            );
+            assert!(!resolved_mask.is_relative());
+        }
+
+        for (desc, span) in [(">start+30", 1..9), ("last:2", 0..6), ("> end+x", 2..7)] {
+            let err = MaskFilter::from_str(desc).unwrap_err();
+            let diagnostic = err.diagnostic().unwrap();
+            assert_eq!(diagnostic.span, span, "invalid span for \"{}\"", desc);
+            assert_eq!(
+                diagnostic.expected,
+                Expectation::RelativeEpoch,
+                "for \"{}\"",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    fn mask_regions() {
+        assert_masks([
+            (
+                "region=circle(48.8,2.3,5km)",
+                MaskOperand::Inside,
+                FilterItem::RegionItem(Region::Circle {
+                    latitude: 48.8,
+                    longitude: 2.3,
+                    radius: 5000.0,
+                }),
+            ),
+            (
+                "region in box(40,-5,50,10)",
+                MaskOperand::Inside,
+                FilterItem::RegionItem(Region::BoundingBox {
+                    min_latitude: 40.0,
+                    min_longitude: -5.0,
+                    max_latitude: 50.0,
+                    max_longitude: 10.0,
+                }),
+            ),
+            (
+                "region != altitude(0..500)",
+                MaskOperand::Outside,
+                FilterItem::RegionItem(Region::Altitude(Interval::new(0.0, 500.0))),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("region=circle(48.8,2.3)", 7..23, Expectation::Region),
+            ("region>box(40,-5,50,10)", 6..7, Expectation::Operand),
+        ]);
+    }
+
+    #[test]
+    fn mask_stations() {
+        assert_masks([
+            (
+                "marker=ABMF,AJAC",
+                MaskOperand::Equals,
+                FilterItem::MarkerItem(vec!["ABMF".to_string(), "AJAC".to_string()]),
+            ),
+            (
+                "marker != abmf00glp",
+                MaskOperand::NotEquals,
+                FilterItem::MarkerItem(vec!["ABMF00GLP".to_string()]),
+            ),
+            (
+                "domes=10003M009",
+                MaskOperand::Equals,
+                FilterItem::DomesItem(vec![DOMES::from_str("10003M009").unwrap()]),
+            ),
+            (
+                "domes = 10003M009, 40451S001",
+                MaskOperand::Equals,
+                FilterItem::DomesItem(vec![
+                    DOMES::from_str("10003M009").unwrap(),
+                    DOMES::from_str("40451S001").unwrap(),
+                ]),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("marker=ABMF,A JAC", 12..17, Expectation::Marker),
+            ("marker=", 7..7, Expectation::Marker),
+            ("domes=10003M009,10003X009", 16..25, Expectation::DOMES),
+            ("domes in 1..2", 9..13, Expectation::DOMES),
+        ]);
+    }
+
+    #[test]
+    fn mask_satellite_identities() {
+        assert_masks([
+            (
+                "cospar=1998-017A",
+                MaskOperand::Equals,
+                FilterItem::CosparItem(vec![COSPAR::from_str("1998-017A").unwrap()]),
+            ),
+            (
+                "cospar != 1998-017A, 2018-078B",
+                MaskOperand::NotEquals,
+                FilterItem::CosparItem(vec![
+                    COSPAR::from_str("1998-017A").unwrap(),
+                    COSPAR::from_str("2018-078B").unwrap(),
+                ]),
+            ),
+            (
+                "sbas=EGNOS,WAAS",
+                MaskOperand::Equals,
+                FilterItem::SbasItem(vec![Constellation::EGNOS, Constellation::WAAS]),
+            ),
+            (
+                "sbas != gagan, msas, sdcm",
+                MaskOperand::NotEquals,
+                FilterItem::SbasItem(vec![
+                    Constellation::GAGAN,
+                    Constellation::MSAS,
+                    Constellation::SDCM,
+                ]),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("cospar=1998017A", 7..15, Expectation::COSPAR),
+            ("sbas=EGNOS,GPS", 11..14, Expectation::SBAS),
+        ]);
+
+        let err = MaskFilter::from_str("sbas=EGNOD").unwrap_err();
+        assert_eq!(
+            err.diagnostic().unwrap().suggestion.as_deref(),
+            Some("EGNOS")
+        );
+    }
+
+    #[test]
+    fn mask_navigation() {
+        let nav = |field, value| FilterItem::NavItem(NavValue { field, value });
+        assert_masks([
+            (
+                "health!=0",
+                MaskOperand::NotEquals,
+                nav(NavField::Health, 0.0),
+            ),
+            (
+                "ura<=2.0",
+                MaskOperand::LowerEquals,
+                nav(NavField::URA, 2.0),
+            ),
+            ("IODE = 12", MaskOperand::Equals, nav(NavField::IODE, 12.0)),
+            (
+                "tgd>-5E-9",
+                MaskOperand::GreaterThan,
+                nav(NavField::TGD, -5E-9),
+            ),
+            (
+                "toe>=86400",
+                MaskOperand::GreaterEquals,
+                nav(NavField::Toe, 86400.0),
+            ),
+            (
+                "ura in 0..2",
+                MaskOperand::Inside,
+                FilterItem::NavRangeItem(NavRange {
+                    field: NavField::URA,
+                    interval: Interval::new(0.0, 2.0),
+                }),
+            ),
+            (
+                "iode != [1, 10]",
+                MaskOperand::Outside,
+                FilterItem::NavRangeItem(NavRange {
+                    field: NavField::IODE,
+                    interval: Interval::inclusive(1.0, 10.0),
+                }),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("health!=ok", 8..10, Expectation::Integer),
+            ("iodc=1.5", 5..8, Expectation::Integer),
+            ("sisa<high", 5..9, Expectation::Float),
+            ("ura<=nan", 5..8, Expectation::Float),
+            ("iode in 0.5..2", 8..14, Expectation::Interval),
+            ("ura in high", 7..11, Expectation::Interval),
+        ]);
+
+        let err = MaskFilter::from_str("helth=0").unwrap_err();
+        assert_eq!(
+            err.diagnostic().unwrap().suggestion.as_deref(),
+            Some("health")
+        );
+    }
+
+    #[test]
+    fn mask_observation_flags() {
+        assert_masks([
+            ("lli!=0", MaskOperand::NotEquals, FilterItem::LliItem(0)),
+            ("lli = 1", MaskOperand::Equals, FilterItem::LliItem(1)),
+            ("ssi>=5", MaskOperand::GreaterEquals, FilterItem::SsiItem(5)),
+            (
+                "epochflag=ok,powerfailure",
+                MaskOperand::Equals,
+                FilterItem::EpochFlagItem(vec![EpochFlag::Ok, EpochFlag::PowerFailure]),
+            ),
+            (
+                "epochflag != 6",
+                MaskOperand::NotEquals,
+                FilterItem::EpochFlagItem(vec![EpochFlag::CycleSlip]),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("lli>8", 4..5, Expectation::IntegerRange(0, 7)),
+            ("lli>=2", 3..5, Expectation::Operand),
+            ("ssi in 1..5", 7..11, Expectation::IntegerRange(0, 9)),
+            ("epochflag=ok,powerfailur", 13..24, Expectation::EpochFlag),
+        ]);
+    }
+
+    #[test]
+    fn mask_satellite_classes() {
+        assert_masks([
+            (
+                "orbit=MEO",
+                MaskOperand::Equals,
+                FilterItem::OrbitItem(vec![OrbitClass::MEO]),
+            ),
+            (
+                "orbit != geo, igso",
+                MaskOperand::NotEquals,
+                FilterItem::OrbitItem(vec![OrbitClass::GEO, OrbitClass::IGSO]),
+            ),
+            (
+                "block=IIIA",
+                MaskOperand::Equals,
+                FilterItem::BlockItem(vec!["IIIA".to_string()]),
+            ),
+            (
+                "block = foc, IIR-M",
+                MaskOperand::Equals,
+                FilterItem::BlockItem(vec!["FOC".to_string(), "IIR-M".to_string()]),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("orbit=HEO", 6..9, Expectation::OrbitClass),
+            ("block=IIIA,II R", 11..15, Expectation::Block),
+        ]);
+    }
+
+    #[test]
+    fn mask_clock_values() {
+        for (desc, operand, quantity, value) in [
+            (
+                "clk>1ms",
+                MaskOperand::GreaterThan,
+                ClockQuantity::Offset,
+                1.0E-3,
+            ),
+            (
+                "clock <= 10 ns",
+                MaskOperand::LowerEquals,
+                ClockQuantity::Offset,
+                1.0E-8,
+            ),
+            (
+                "clkdrift<1e-9",
+                MaskOperand::LowerThan,
+                ClockQuantity::Drift,
+                1.0E-9,
+            ),
+            (
+                "clkdriftrate>=-1E-15 s/s^2",
+                MaskOperand::GreaterEquals,
+                ClockQuantity::DriftRate,
+                -1.0E-15,
+            ),
+        ] {
+            let mask = MaskFilter::from_str(desc).unwrap();
+            let item = FilterItem::ClockValueItem(ClockValue { quantity, value });
+            assert_eq!(mask, MaskFilter { operand, item }, "for \"{}\"", desc);
+            assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+        }
+
+        // clock offsets are still selected without operand
+        assert_eq!(
+            MaskFilter::from_str("clk").unwrap().item,
+            FilterItem::ClockItem
+        );
+
+        for (desc, span) in [("clk>1 h", 4..7), ("clkdrift in 0..1", 12..16)] {
+            let err = MaskFilter::from_str(desc).unwrap_err();
+            let diagnostic = err.diagnostic().unwrap();
+            assert_eq!(diagnostic.span, span, "invalid span for \"{}\"", desc);
+            assert_eq!(diagnostic.expected, Expectation::Clock, "for \"{}\"", desc);
+        }
+    }
+
+    #[test]
+    fn mask_carriers() {
+        assert_masks([
+            (
+                "=E5a,B2a",
+                MaskOperand::Equals,
+                FilterItem::CarrierItem(vec![Carrier::E5a, Carrier::B2a]),
+            ),
+            (
+                "carrier=L5,E5a",
+                MaskOperand::Equals,
+                FilterItem::CarrierItem(vec![Carrier::L5, Carrier::E5a]),
+            ),
+            (
+                "carrier=E1,E5,E5a",
+                MaskOperand::Equals,
+                FilterItem::CarrierItem(vec![Carrier::E1, Carrier::E5, Carrier::E5a]),
+            ),
+            (
+                "carrier = L1, G1",
+                MaskOperand::Equals,
+                FilterItem::CarrierItem(vec![Carrier::L1, Carrier::G1]),
+            ),
+            (
+                "carrier!=E1",
+                MaskOperand::NotEquals,
+                FilterItem::CarrierItem(vec![Carrier::E1]),
+            ),
+            (
+                "freq>1500MHz",
+                MaskOperand::GreaterThan,
+                FilterItem::FrequencyItem(1500.0),
+            ),
+            (
+                "frequency <= 1.3 GHz",
+                MaskOperand::LowerEquals,
+                FilterItem::FrequencyItem(1300.0),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("freq>fast", 5..9, Expectation::Frequency),
+            ("carrier=L1, X5", 12..14, Expectation::Carrier),
+            ("freq in 1100..1300", 8..18, Expectation::Frequency),
+        ]);
+    }
+    #[test]
+    fn mask_calendar() {
+        let mask = MaskFilter::from_str("tod>=22:00 UTC").unwrap();
+        assert_eq!(
+            mask,
+            MaskFilter {
+                operand: MaskOperand::GreaterEquals,
+                item: FilterItem::TimeOfDayItem(TimeOfDay::new(22, 0, 0, TimeScale::UTC)),
+            }
+        );
+        assert_eq!(mask.to_string(), "tod>=22:00 UTC");
+
+        let mask = MaskFilter::from_str("dow!=sat,sun").unwrap();
+        assert_eq!(mask.operand, MaskOperand::NotEquals);
+        assert_eq!(
+            mask.item,
+            FilterItem::DayOfWeekItem(DaysOfWeek {
+                days: vec![Weekday::Saturday, Weekday::Sunday],
+                timescale: TimeScale::UTC,
+            })
+        );
+        assert_eq!(MaskFilter::from_str(&mask.to_string()).unwrap(), mask);
+
+        assert_diagnostics(&[
+            ("tod < 25:00", 6..11, Expectation::TimeOfDay),
+            ("dow = sat, sunny GPST", 6..21, Expectation::DaysOfWeek),
+        ]);
+    }
+    #[test]
+    fn mask_calendar_periods() {
+        let epoch = |desc: &str| Epoch::from_str(desc).unwrap();
+        let week_2310 = Interval::new(
+            epoch("2024-04-14T00:00:00 GPST"),
+            epoch("2024-04-21T00:00:00 GPST"),
+        );
+        assert_masks([
+            (
+                "week=2310",
+                MaskOperand::Inside,
+                FilterItem::EpochRangeItem(week_2310.clone()),
+            ),
+            (
+                "week != 2310 GPST",
+                MaskOperand::Outside,
+                FilterItem::EpochRangeItem(week_2310.clone()),
+            ),
+            (
+                "week>=2310",
+                MaskOperand::GreaterEquals,
+                FilterItem::EpochItem(week_2310.start),
+            ),
+            (
+                "week>2310",
+                MaskOperand::GreaterEquals,
+                FilterItem::EpochItem(week_2310.end),
+            ),
+            (
+                "week<=2310",
+                MaskOperand::LowerThan,
+                FilterItem::EpochItem(week_2310.end),
+            ),
+            (
+                "week in 2310..=2310",
+                MaskOperand::Inside,
+                FilterItem::EpochRangeItem(week_2310.clone()),
+            ),
+            (
+                "week=1000 BDS",
+                MaskOperand::Inside,
+                FilterItem::EpochRangeItem(Interval::new(
+                    epoch("2025-03-02T00:00:00 BDT"),
+                    epoch("2025-03-09T00:00:00 BDT"),
+                )),
+            ),
+            (
+                "doy=2024-045",
+                MaskOperand::Inside,
+                FilterItem::EpochRangeItem(Interval::new(
+                    epoch("2024-02-14T00:00:00 UTC"),
+                    epoch("2024-02-15T00:00:00 UTC"),
+                )),
+            ),
+            (
+                "doy=045",
+                MaskOperand::Equals,
+                FilterItem::DayOfYearItem(DayOfYear {
+                    day: 45,
+                    timescale: TimeScale::UTC,
+                }),
+            ),
+            (
+                "tow<86400",
+                MaskOperand::LowerThan,
+                FilterItem::TimeOfWeekItem(TimeOfWeek {
+                    time: Duration::from_str("1 d").unwrap(),
+                    timescale: TimeScale::GPST,
+                }),
+            ),
+            (
+                "mjd>60000",
+                MaskOperand::GreaterEquals,
+                FilterItem::EpochItem(epoch("2023-02-26T00:00:00 UTC")),
+            ),
+            (
+                "mjd>60000.5",
+                MaskOperand::GreaterThan,
+                FilterItem::EpochItem(epoch("2023-02-25T12:00:00 UTC")),
+            ),
+        ]);
+
+        assert_diagnostics(&[
+            ("week=23x0", 5..9, Expectation::Week),
+            ("doy=2023-366", 4..12, Expectation::DayOfYear),
+            ("tow in 0..3600", 7..14, Expectation::TimeOfWeek),
+            ("mjd<-1", 4..6, Expectation::MJD),
+        ]);
+    }
+    #[test]
+    fn mask_observables() {
         let mask = MaskFilter::from_str("=L1C,S1C,D1P,C1W").unwrap();
         assert_eq!(
             mask,
             MaskFilter {
                 operand: MaskOperand::Equals,
-                item: FilterItem::ComplexItem(vec![
-                    "L1C".to_string(),
-                    "S1C".to_string(),
-                    "D1P".to_string(),
-                    "C1W".to_string()
-                ])
+                item: FilterItem::ObservableItem(
+                    ["L1C", "S1C", "D1P", "C1W"]
+                        .iter()
+                        .map(|o| Observable::from_str(o).unwrap())
+                        .collect()
+                )
             }
         );
+
+        let mask = MaskFilter::from_str("!=?1C").unwrap();
+        assert_eq!(mask.operand, MaskOperand::NotEquals);
+        assert_eq!(mask.to_string(), "!=?1C");
+
+        // RINEX 2 observables that also describe satellites
+        let observables = |obs: &[&str]| {
+            FilterItem::ObservableItem(
+                obs.iter()
+                    .map(|o| Observable::from_str(o).unwrap())
+                    .collect(),
+            )
+        };
+        assert_masks([
+            (
+                "=L1,C1,P2,S1",
+                MaskOperand::Equals,
+                observables(&["L1", "C1", "P2", "S1"]),
+            ),
+            ("obs=C1,C2", MaskOperand::Equals, observables(&["C1", "C2"])),
+            ("obs != S1", MaskOperand::NotEquals, observables(&["S1"])),
+            (
+                "sv=C1,C2",
+                MaskOperand::Equals,
+                FilterItem::SvItem(vec![
+                    SV::new(Constellation::BeiDou, 1),
+                    SV::new(Constellation::BeiDou, 2),
+                ]),
+            ),
+        ]);
+        assert_eq!(
+            MaskFilter::from_str("=C1,C2").unwrap().item,
+            FilterItem::SvItem(vec![
+                SV::new(Constellation::BeiDou, 1),
+                SV::new(Constellation::BeiDou, 2),
+            ])
+        );
+        assert_diagnostics(&[("obs=C1,G08", 7..10, Expectation::Observable)]);
     }
 }
@@ -2,45 +2,213 @@
 use std::str::FromStr;
 use thiserror::Error;
 
+use crate::merge::{Error as MergeError, Merge};
+use hifitime::Epoch;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+mod analysis;
+pub use analysis::{AnalysisWarning, FilterAnalysis};
+
+mod diagnostic;
+pub use diagnostic::{Diagnostic, Expectation as DiagnosticExpectation};
+
+mod interval;
+pub use interval::{Error as IntervalError, Interval};
+
+mod calendar;
+pub use calendar::{DayOfYear, DaysOfWeek, Error as CalendarError, TimeOfDay, TimeOfWeek};
+
+mod carrier;
+pub use carrier::{Carrier, Error as CarrierError};
+
+mod relative;
+pub use relative::{Error as RelativeError, RelativeEpoch, RelativeSpan, TimeAnchor, TimeSpan};
+
+mod snr;
+pub use snr::{Error as SNRTemplateError, SNRTemplate, TemplateInterpolation};
+
+mod region;
+pub use region::{Error as RegionError, GeodeticPosition, Region};
+
+mod flag;
+pub use flag::{EpochFlag, Error as FlagError, LLI_MAX, SSI_MAX, lli_matches};
+
+mod satellite;
+pub use satellite::{Error as SatelliteError, OrbitClass, SatelliteDB, SatelliteInfo};
+
+mod clock;
+pub use clock::{ClockQuantity, ClockValue, Error as ClockError};
+
+mod navigation;
+pub use navigation::{Error as NavigationError, NavField, NavRange, NavValue};
+
+mod observable;
+pub use observable::{Attribute, Error as ObservableError, Observable, Physics};
+
 mod item;
 pub use item::{FilterItem, ItemError};
 
 mod mask;
 pub use mask::{Error as MaskError, MaskFilter, MaskOperand, Masking};
 
+mod report;
+pub use report::{Census, DataCensus, MaskReport};
+
+mod expr;
+pub use expr::{Error as FilterExprError, FilterExpr};
+
 mod decim;
 pub use decim::{Decimate, DecimationFilter, DecimationFilterType, Error as DecimationError};
 
+mod smoothing;
+pub use smoothing::{Error as SmoothingError, Smooth, SmoothingFilter, SmoothingType};
+
+mod interp;
+pub use interp::{Error as InterpError, InterpFilter, InterpMethod, InterpSeries, Interpolate};
+
 mod split;
 pub use split::Split;
 
+#[cfg(feature = "serde")]
+pub(crate) mod schema;
+
+mod pipeline;
+pub use pipeline::{Error as PipelineError, Pipeline, Step};
+
 mod time;
 pub use time::{TimeCorrection, TimeCorrectionError, TimeCorrectionsDB, Timeshift};
 
 /// Preprocessing Trait is usually implemented by GNSS data
 /// to preprocess prior further analysis.
+/// [Filter] dispatching requires [Smooth] and [Interpolate] as well,
+/// which remain optional for datasets that only need [Masking] and [Decimate].
 pub trait Preprocessing: Masking + Decimate + Split + Timeshift {
     /// Apply [Filter] algorithm on immutable dataset.
     fn filter(&self, filter: &Filter) -> Self
     where
-        Self: Sized,
+        Self: Sized + Smooth + Interpolate,
     {
         match filter {
             Filter::Mask(f) => self.mask(f),
             Filter::Decimation(f) => self.decimate(f),
+            Filter::Smoothing(f) => self.smooth(f),
+            Filter::Interp(f) => self.interpolate(f),
         }
     }
     /// Apply [Filter] algorithm on mutable dataset.
-    fn filter_mut(&mut self, filter: &Filter) {
+    fn filter_mut(&mut self, filter: &Filter)
+    where
+        Self: Smooth + Interpolate,
+    {
         match filter {
             Filter::Mask(f) => self.mask_mut(f),
             Filter::Decimation(f) => self.decimate_mut(f),
+            Filter::Smoothing(f) => self.smooth_mut(f),
+            Filter::Interp(f) => self.interpolate_mut(f),
+        }
+    }
+    /// Apply [Filter] algorithm on mutable dataset, and report what masks removed.
+    /// Returns None for other [Filter]s.
+    /// [FilterExpr]s are reported by [Self::filter_expr_mut_with_report].
+    fn filter_mut_with_report(&mut self, filter: &Filter) -> Option<MaskReport>
+    where
+        Self: Census + Smooth + Interpolate,
+    {
+        match filter {
+            Filter::Mask(f) => Some(self.mask_mut_with_report(f)),
+            _ => {
+                self.filter_mut(filter);
+                None
+            }
+        }
+    }
+    /// Apply [FilterExpr] on immutable dataset.
+    /// AND terms are applied successively, while OR branches are
+    /// evaluated separately and then [Merge]d together.
+    /// Negations are pushed down to the [MaskFilter]s (see [FilterExpr::normalized]).
+    /// Relative epochs are resolved against the [TimeSpan] of this dataset,
+    /// prior applying any term.
+    fn filter_expr(&self, expr: &FilterExpr) -> Result<Self, MergeError>
+    where
+        Self: Sized + Merge + TimeSpan,
+    {
+        apply_expr(self, &resolved_expr(self, expr))
+    }
+    /// Apply [FilterExpr] on mutable dataset.
+    /// See [Self::filter_expr] for more information.
+    fn filter_expr_mut(&mut self, expr: &FilterExpr) -> Result<(), MergeError>
+    where
+        Self: Sized + Merge + TimeSpan,
+    {
+        let expr = resolved_expr(self, expr);
+        apply_expr_mut(self, &expr)
+    }
+    /// Apply [FilterExpr] on mutable dataset, and report what it removed.
+    /// The [MaskReport] lists all [MaskFilter]s of the expression, while its
+    /// [DataCensus] are counted prior and after the entire expression,
+    /// because OR branches are [Merge]d together.
+    fn filter_expr_mut_with_report(&mut self, expr: &FilterExpr) -> Result<MaskReport, MergeError>
+    where
+        Self: Sized + Merge + TimeSpan + Census,
+    {
+        let before = self.census();
+        let expr = resolved_expr(self, expr);
+        apply_expr_mut(self, &expr)?;
+        let after = self.census();
+        Ok(MaskReport {
+            masks: expr.masks().into_iter().cloned().collect(),
+            removed: before.difference(&after),
+            before,
+            after,
+        })
+    }
+}
+
+/// Resolves the relative epochs of this [FilterExpr] against the [TimeSpan] of this dataset
+fn resolved_expr<T: TimeSpan>(data: &T, expr: &FilterExpr) -> FilterExpr {
+    match data.time_span() {
+        Some(span) => expr.resolved(&span),
+        None => expr.clone(),
+    }
+}
+
+/// Applies a resolved [FilterExpr], see [Preprocessing::filter_expr]
+fn apply_expr<T: Preprocessing + Merge>(data: &T, expr: &FilterExpr) -> Result<T, MergeError> {
+    match expr {
+        FilterExpr::Mask(f) => Ok(data.mask(f)),
+        FilterExpr::And(lhs, rhs) => apply_expr(&apply_expr(data, lhs)?, rhs),
+        FilterExpr::Or(lhs, rhs) => apply_expr(data, lhs)?.merge(&apply_expr(data, rhs)?),
+        FilterExpr::Not(inner) => apply_expr(data, &inner.complement()),
+    }
+}
+
+/// Applies a resolved [FilterExpr] on mutable dataset, see [Preprocessing::filter_expr_mut]
+fn apply_expr_mut<T: Preprocessing + Merge>(
+    data: &mut T,
+    expr: &FilterExpr,
+) -> Result<(), MergeError> {
+    match expr {
+        FilterExpr::Mask(f) => {
+            data.mask_mut(f);
+            Ok(())
+        }
+        FilterExpr::And(lhs, rhs) => {
+            apply_expr_mut(data, lhs)?;
+            apply_expr_mut(data, rhs)
+        }
+        _ => {
+            *data = apply_expr(data, expr)?;
+            Ok(())
         }
     }
 }
 
 /// Repair
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Repair {
     /// Repairs zero phase range and decoded range values,
     /// that are physically incorrect and most likely generated
@@ -48,6 +216,24 @@ pub enum Repair {
     Zero,
 }
 
+impl std::str::FromStr for Repair {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        match content.trim().to_ascii_lowercase().as_str() {
+            "zero" => Ok(Self::Zero),
+            _ => Err(Error::UnknownRepair(content.trim().to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Zero => write!(f, "zero"),
+        }
+    }
+}
+
 pub trait RepairTrait {
     fn repair(&self, r: Repair) -> Self;
     fn repair_mut(&mut self, r: Repair);
@@ -65,19 +251,43 @@ pub enum Error {
     FilterItemError(#[from] ItemError),
     #[error("invalid decimation filter")]
     DecimationFilterParsing(#[from] DecimationError),
+    #[error("invalid smoothing filter")]
+    SmoothingFilterParsing(#[from] SmoothingError),
+    #[error("invalid interpolation filter")]
+    InterpFilterParsing(#[from] InterpError),
+    #[error("unknown repair \"{0}\"")]
+    UnknownRepair(String),
+    #[error("invalid timescale \"{0}\"")]
+    InvalidTimescale(String),
+}
+
+impl Error {
+    /// Returns the [Diagnostic] attached to this error, if any.
+    /// Its span is expressed within the entire [Filter] description.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            Self::MaskFilterParsing(e) => e.diagnostic(),
+            Self::FilterItemError(e) => e.diagnostic(),
+            _ => None,
+        }
+    }
 }
 
 /// Preprocessing filters, to preprocess RINEX data prior further analysis.
 /// Filters can apply either on entire RINEX or subsets.
 /// Refer to [TargetItem] definition to understand which data subsets exist.  
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
 pub enum Filter {
     /// Mask filter, to focus on specific data subsets
     Mask(MaskFilter),
     /// Decimation filter, filters to reduce sample rate
     Decimation(DecimationFilter),
-    // /// Interpolation filter is work in progress and cannot be used at the moment
-    // Interp(InterpFilter),
+    /// Smoothing filter, to smooth data subsets
+    Smoothing(SmoothingFilter),
+    /// Interpolation filter, to resample data subsets
+    Interp(InterpFilter),
 }
 
 impl Filter {
@@ -123,6 +333,17 @@ impl Filter {
     }
 }
 
+impl Filter {
+    /// Resolves the relative epochs of this [Filter] against given time span.
+    /// Only [MaskFilter]s may describe relative epochs, see [MaskFilter::resolved].
+    pub fn resolved(&self, span: &Interval<Epoch>) -> Self {
+        match self {
+            Self::Mask(mask) => Self::Mask(mask.resolved(span)),
+            _ => self.clone(),
+        }
+    }
+}
+
 impl From<MaskFilter> for Filter {
     fn from(mask: MaskFilter) -> Self {
         Self::Mask(mask)
@@ -145,26 +366,66 @@ impl From<DecimationFilter> for Filter {
     }
 }
 
+impl From<SmoothingFilter> for Filter {
+    fn from(smoothing: SmoothingFilter) -> Self {
+        Self::Smoothing(smoothing)
+    }
+}
+
+impl From<InterpFilter> for Filter {
+    fn from(interp: InterpFilter) -> Self {
+        Self::Interp(interp)
+    }
+}
+
+impl std::fmt::Display for Filter {
+    /// Formats this [Filter] so it may be parsed back with [Filter::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Mask(mask) => write!(f, "mask:{}", mask),
+            Self::Decimation(decim) => write!(f, "decim:{}", decim),
+            Self::Smoothing(smoothing) => write!(f, "smooth:{}", smoothing),
+            Self::Interp(interp) => write!(f, "interp:{}", interp),
+        }
+    }
+}
+
 impl std::str::FromStr for Filter {
     type Err = Error;
     fn from_str(content: &str) -> Result<Self, Self::Err> {
-        let items: Vec<&str> = content.split(':').collect();
+        let (identifier, payload) = match content.split_once(':') {
+            Some((identifier, payload)) => (identifier.trim(), Some(payload)),
+            None => (content.trim(), None),
+        };
+
+        let is_identifier = matches!(identifier, "decim" | "smooth" | "interp" | "mask");
+        if is_identifier && payload.is_none() {
+            return Err(Error::InvalidFilter);
+        }
+
+        let payload = payload.unwrap_or_default();
+        // offset of the payload, past the identifier and ':'
+        let offset = content.len() - payload.len();
 
-        let identifier = items[0].trim();
         if identifier.eq("decim") {
-            let offset = 6; //"decim:"
             Ok(Self::Decimation(DecimationFilter::from_str(
-                content[offset..].trim(),
+                payload.trim(),
             )?))
+        } else if identifier.eq("smooth") {
+            Ok(Self::Smoothing(SmoothingFilter::from_str(payload.trim())?))
+        } else if identifier.eq("interp") {
+            Ok(Self::Interp(InterpFilter::from_str(payload.trim())?))
         } else if identifier.eq("mask") {
-            let offset = 5; //"mask:"
-            Ok(Self::Mask(MaskFilter::from_str(content[offset..].trim())?))
+            Ok(Self::Mask(
+                MaskFilter::from_str(payload).map_err(|e| e.shifted(offset))?,
+            ))
         } else {
             // assume Mask (omitted identifier)
-            if let Ok(f) = MaskFilter::from_str(content.trim()) {
-                Ok(Self::Mask(f))
-            } else {
-                Err(Error::UnknownFilterType(content.to_string()))
+            match MaskFilter::from_str(content) {
+                Ok(f) => Ok(Self::Mask(f)),
+                // this description is most likely a faulty mask
+                Err(e) if e.diagnostic().is_some() => Err(Error::MaskFilterParsing(e)),
+                Err(_) => Err(Error::UnknownFilterType(content.to_string())),
             }
         }
     }
@@ -207,7 +468,7 @@ mod test {
             "decim:10 min",
             "decim:1 hour",
             "decim:10 min:l1c",
-            "decim:1 hour:L1C,L2C,L3C",
+            "decim:1 hour:L1C,L2C,L5Q",
         ] {
             let filt = Filter::from_str(desc);
             assert!(filt.is_ok(), "Filter::from_str failed on \"{}\"", desc);
@@ -222,9 +483,71 @@ mod test {
             "smooth:mov:10 min:clk",
             "smooth:hatch",
             "smooth:hatch:l1c",
+            " smooth:hatch",
         ] {
             let filt = Filter::from_str(desc);
             assert!(filt.is_ok(), "Filter::from_str failed on \"{}\"", desc);
         }
+        /*
+         * Identifiers without payload
+         */
+        for desc in ["smooth", " smooth ", "interp", "decim", "mask"] {
+            assert!(
+                matches!(Filter::from_str(desc), Err(Error::InvalidFilter)),
+                "Filter::from_str did not reject \"{}\"",
+                desc
+            );
+        }
+        /*
+         * Diagnostics are expressed within the entire description
+         */
+        for (desc, span) in [
+            ("mask:e>100", 7..10),
+            ("e > 100", 4..7),
+            ("mask: GSP", 6..9),
+        ] {
+            let err = Filter::from_str(desc).unwrap_err();
+            assert_eq!(err.diagnostic().unwrap().span, span, "for \"{}\"", desc);
+        }
+    }
+    #[test]
+    fn display_round_trip() {
+        for descriptor in [
+            "GPS",
+            "!= GPS, GAL",
+            "=G08, G09, G10",
+            ">G08, G09",
+            "!=GAL,G08",
+            "e>10",
+            " e <= 45.5",
+            "a>=120",
+            "snr<35",
+            "clk",
+            "iode,crs",
+            "=L1C,S1C,D1P,C1W",
+            ">2020-01-14T00:31:55 UTC",
+            "<=2020-01-14T00:31:55.123 GPST",
+            "=1 h 30 min",
+            "=2020-01-14T00:00:00 UTC, 2020-01-15T00:00:00 UTC",
+            "elev in (10, 60]",
+            "azimuth !in 90..=180",
+            "mask:in 2020-01-14T00:00:00 UTC..2020-01-15T00:00:00 UTC",
+            "decim:10",
+            "decim:10 min",
+            "decim:1 hour:L1C,L2C,L5Q",
+            "decim:30 s:G08,G09",
+            "smooth:hatch",
+            "smooth:hatch:C1C,C2P",
+            "smooth:mov:10 min:clk",
+            "interp:linear:2020-01-14T00:00:00 UTC..2020-01-15T00:00:00 UTC/30 s",
+            "interp:lagrange(9):2020-01-14T00:00:00 GPST,2020-01-14T00:00:30 GPST:G08",
+        ] {
+            let filter = Filter::from_str(descriptor).unwrap();
+            let formatted = filter.to_string();
+            let parsed = Filter::from_str(&formatted).unwrap_or_else(|e| {
+                panic!("failed to parse \"{}\" back: {}", formatted, e);
+            });
+            assert_eq!(parsed, filter, "round trip failed for \"{}\"", descriptor);
+        }
     }
 }
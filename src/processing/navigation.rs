@@ -0,0 +1,281 @@
+//! Navigation message fields, for NAV data quality control
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::processing::Interval;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Navigation fields parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown navigation field \"{0}\"")]
+    UnknownField(String),
+    #[error("invalid {0} value \"{1}\"")]
+    InvalidValue(NavField, String),
+}
+
+/// [NavField] designates a navigation message field,
+/// described by its usual name in lowercase, like `iode` or `crs`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum NavField {
+    /// Issue of Data Ephemeris (GPS, QZSS, BeiDou)
+    IODE,
+    /// Issue of Data Clock (GPS, QZSS, BeiDou)
+    IODC,
+    /// Issue of Data (Galileo)
+    IODnav,
+    /// SV health flags, as an integer
+    Health,
+    /// User Range Accuracy, in meters
+    URA,
+    /// Signal In Space Accuracy (Galileo), in meters
+    SISA,
+    /// Total Group Delay, in seconds
+    TGD,
+    /// Orbit radius sine correction term, in meters
+    Crs,
+    /// Orbit radius cosine correction term, in meters
+    Crc,
+    /// Argument of latitude cosine correction term, in radians
+    Cuc,
+    /// Argument of latitude sine correction term, in radians
+    Cus,
+    /// Inclination cosine correction term, in radians
+    Cic,
+    /// Inclination sine correction term, in radians
+    Cis,
+    /// Time of ephemeris, in seconds of week
+    Toe,
+}
+
+impl NavField {
+    /// All known [NavField]s
+    pub const ALL: [Self; 14] = [
+        Self::IODE,
+        Self::IODC,
+        Self::IODnav,
+        Self::Health,
+        Self::URA,
+        Self::SISA,
+        Self::TGD,
+        Self::Crs,
+        Self::Crc,
+        Self::Cuc,
+        Self::Cus,
+        Self::Cic,
+        Self::Cis,
+        Self::Toe,
+    ];
+
+    /// Returns the name of this [NavField], which is also its mask target
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IODE => "iode",
+            Self::IODC => "iodc",
+            Self::IODnav => "iodnav",
+            Self::Health => "health",
+            Self::URA => "ura",
+            Self::SISA => "sisa",
+            Self::TGD => "tgd",
+            Self::Crs => "crs",
+            Self::Crc => "crc",
+            Self::Cuc => "cuc",
+            Self::Cus => "cus",
+            Self::Cic => "cic",
+            Self::Cis => "cis",
+            Self::Toe => "toe",
+        }
+    }
+
+    /// Returns true if this [NavField] only takes integer values
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Self::IODE | Self::IODC | Self::IODnav | Self::Health)
+    }
+
+    /// Returns true if this [NavField] may take given value:
+    /// integer fields only take positive integer values.
+    fn accepts(&self, value: f64) -> bool {
+        if self.is_integer() {
+            value >= 0.0 && value <= u32::MAX as f64 && value.fract() == 0.0
+        } else {
+            value.is_finite()
+        }
+    }
+}
+
+impl FromStr for NavField {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        Self::ALL
+            .iter()
+            .find(|field| field.name().eq_ignore_ascii_case(c))
+            .copied()
+            .ok_or(Error::UnknownField(c.to_string()))
+    }
+}
+
+impl std::fmt::Display for NavField {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// [NavValue] is the value of a [NavField], that may be compared
+/// directly to the navigation messages content.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawNavValue"))]
+pub struct NavValue {
+    /// [NavField] this value applies to
+    pub field: NavField,
+    /// Value, expressed in the [NavField] unit
+    pub value: f64,
+}
+
+impl NavValue {
+    /// Builds a new [NavValue]. Integer fields only accept
+    /// positive integer values.
+    pub fn new(field: NavField, value: f64) -> Result<Self, Error> {
+        if field.accepts(value) {
+            Ok(Self { field, value })
+        } else {
+            Err(Error::InvalidValue(field, value.to_string()))
+        }
+    }
+
+    /// Parses the value of given [NavField]. Integer fields only accept
+    /// positive integer values.
+    pub fn parse(field: NavField, content: &str) -> Result<Self, Error> {
+        let c = content.trim();
+        let invalid = || Error::InvalidValue(field, c.to_string());
+        let value = if field.is_integer() {
+            u32::from_str(c).map_err(|_| invalid())? as f64
+        } else {
+            f64::from_str(c).map_err(|_| invalid())?
+        };
+        Self::new(field, value).map_err(|_| invalid())
+    }
+}
+
+/// Deserialized [NavValue], prior validation
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawNavValue {
+    field: NavField,
+    value: f64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawNavValue> for NavValue {
+    type Error = Error;
+    fn try_from(raw: RawNavValue) -> Result<Self, Self::Error> {
+        Self::new(raw.field, raw.value)
+    }
+}
+
+impl std::fmt::Display for NavValue {
+    /// Formats the value only, the [NavField] being the mask target
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// [NavRange] is an [Interval] of values of a [NavField], like `ura in 0..2`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawNavRange"))]
+pub struct NavRange {
+    /// [NavField] this [Interval] applies to
+    pub field: NavField,
+    /// [Interval] of values, expressed in the [NavField] unit
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::display_fromstr")
+    )]
+    pub interval: Interval<f64>,
+}
+
+impl NavRange {
+    /// Builds a new [NavRange]. Integer fields only accept
+    /// positive integer bounds.
+    pub fn new(field: NavField, interval: Interval<f64>) -> Result<Self, Error> {
+        if field.accepts(interval.start) && field.accepts(interval.end) {
+            Ok(Self { field, interval })
+        } else {
+            Err(Error::InvalidValue(field, interval.to_string()))
+        }
+    }
+
+    /// Parses an [Interval] of values of given [NavField].
+    pub fn parse(field: NavField, content: &str) -> Result<Self, Error> {
+        let c = content.trim();
+        let interval =
+            Interval::<f64>::from_str(c).map_err(|_| Error::InvalidValue(field, c.to_string()))?;
+        Self::new(field, interval)
+    }
+}
+
+/// Deserialized [NavRange], prior validation
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawNavRange {
+    field: NavField,
+    #[serde(with = "crate::processing::schema::display_fromstr")]
+    interval: Interval<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawNavRange> for NavRange {
+    type Error = Error;
+    fn try_from(raw: RawNavRange) -> Result<Self, Self::Error> {
+        Self::new(raw.field, raw.interval)
+    }
+}
+
+impl std::fmt::Display for NavRange {
+    /// Formats the [Interval] only, the [NavField] being the mask target
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nav_fields() {
+        for field in NavField::ALL {
+            assert_eq!(NavField::from_str(field.name()).unwrap(), field);
+        }
+        assert_eq!(NavField::from_str("IODnav").unwrap(), NavField::IODnav);
+        assert!(NavField::from_str("iod").is_err());
+
+        let health = NavValue::parse(NavField::Health, "0").unwrap();
+        assert_eq!(health.value, 0.0);
+        assert!(NavValue::parse(NavField::Health, "0.5").is_err());
+        assert!(NavValue::parse(NavField::IODE, "-1").is_err());
+
+        let tgd = NavValue::parse(NavField::TGD, "-5.12E-9").unwrap();
+        assert_eq!(tgd.value, -5.12E-9);
+        assert!(NavValue::parse(NavField::URA, "nan").is_err());
+        assert!(NavValue::parse(NavField::TGD, "inf").is_err());
+        assert!(NavRange::parse(NavField::URA, "0..nan").is_err());
+
+        assert!(NavValue::new(NavField::IODC, 12.0).is_ok());
+        assert!(NavValue::new(NavField::IODC, 1.5).is_err());
+        assert!(NavValue::new(NavField::Health, -1.0).is_err());
+        assert!(NavValue::new(NavField::URA, f64::NAN).is_err());
+
+        let ura = NavRange::parse(NavField::URA, "0..2.5").unwrap();
+        assert_eq!(ura.interval, Interval::new(0.0, 2.5));
+        assert!(NavRange::parse(NavField::IODE, "[1, 10]").is_ok());
+        assert!(NavRange::parse(NavField::IODE, "0.5..2").is_err());
+        assert!(NavRange::parse(NavField::Health, "-1..1").is_err());
+    }
+}
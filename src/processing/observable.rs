@@ -0,0 +1,306 @@
+//! Observable codes, following the RINEX naming conventions
+use std::str::FromStr;
+use thiserror::Error;
+
+/// [Observable] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid observable \"{0}\"")]
+    InvalidObservable(String),
+    #[error("unknown physics '{0}'")]
+    UnknownPhysics(char),
+    #[error("invalid frequency band '{0}'")]
+    InvalidBand(char),
+    #[error("invalid tracking code attribute '{0}'")]
+    InvalidAttribute(char),
+}
+
+/// Physics of an [Observable], described by its first letter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Physics {
+    /// Carrier phase (`L`)
+    Phase,
+    /// Pseudo range (`C`)
+    PseudoRange,
+    /// P-code pseudo range (`P`), only found in RINEX 2
+    PrecisePseudoRange,
+    /// Doppler shift (`D`)
+    Doppler,
+    /// Signal strength (`S`)
+    SNR,
+}
+
+impl Physics {
+    fn from_char(c: char) -> Result<Self, Error> {
+        match c.to_ascii_uppercase() {
+            'L' => Ok(Self::Phase),
+            'C' => Ok(Self::PseudoRange),
+            'P' => Ok(Self::PrecisePseudoRange),
+            'D' => Ok(Self::Doppler),
+            'S' => Ok(Self::SNR),
+            _ => Err(Error::UnknownPhysics(c)),
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Self::Phase => 'L',
+            Self::PseudoRange => 'C',
+            Self::PrecisePseudoRange => 'P',
+            Self::Doppler => 'D',
+            Self::SNR => 'S',
+        }
+    }
+}
+
+impl std::fmt::Display for Physics {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Frequency bands of RINEX 2 observables
+const RINEX2_BANDS: &[u8] = &[1, 2, 5, 6, 7, 8];
+
+/// Tracking code attributes of RINEX 3 and 4 observables, all bands included
+const ATTRIBUTES: &str = "ABCDEILMNPQSWXYZ";
+
+/// Returns the tracking code attributes of RINEX 3 and 4 observables
+/// on given frequency band, all constellations included.
+fn band_attributes(band: u8) -> &'static str {
+    match band {
+        1 => "ABCDELMNPSWXYZ",
+        2 => "CDILMNPQSWXY",
+        3 => "IQX",
+        4 => "ABX",
+        5 => "ABCDIPQXZ",
+        6 => "ABCDEILPQSXZ",
+        7 => "DIPQXZ",
+        8 => "DIPQX",
+        9 => "ABCX",
+        _ => "",
+    }
+}
+
+/// Tracking code [Attribute] of an [Observable]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Attribute {
+    /// RINEX 2 observables do not have any attribute, like `L1`
+    Absent,
+    /// Tracking code of RINEX 3 and 4 observables, like `C` in `L1C`
+    Code(char),
+}
+
+/// [Observable] code, made of a [Physics], a frequency band number
+/// and a tracking code [Attribute], like `L1C` (RINEX 3 and 4), or `L1` (RINEX 2).
+///
+/// [Observable]s may also describe a pattern, `None` fields being wildcards:
+/// - `?` matches any single field, like `?1C` that matches all `1C` observables,
+///   or `L1?` that matches `L1`, `L1C` and `L1W`
+/// - `*` matches all remaining fields, like `L*` that matches all phase observables.
+///
+/// RINEX 2 observables are exact: `L1` only matches `L1`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Observable {
+    /// [Physics], `None` matches any physics
+    pub physics: Option<Physics>,
+    /// Frequency band number, `None` matches any band
+    pub band: Option<u8>,
+    /// Tracking code [Attribute], `None` matches any attribute
+    pub attribute: Option<Attribute>,
+}
+
+impl Observable {
+    /// Returns true if this [Observable] contains wildcards
+    pub fn is_pattern(&self) -> bool {
+        self.physics.is_none() || self.band.is_none() || self.attribute.is_none()
+    }
+
+    /// Returns true if given [Observable] matches this (possible) pattern
+    pub fn matches(&self, observable: &Self) -> bool {
+        self.physics.is_none_or(|p| observable.physics == Some(p))
+            && self.band.is_none_or(|b| observable.band == Some(b))
+            && self
+                .attribute
+                .is_none_or(|a| observable.attribute == Some(a))
+    }
+}
+
+impl FromStr for Observable {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let invalid = || Error::InvalidObservable(c.to_string());
+
+        let (fields, wildcard) = match c.strip_suffix('*') {
+            Some(fields) => (fields, true),
+            None => (c, false),
+        };
+
+        let chars = fields.chars().collect::<Vec<_>>();
+
+        if fields.contains('*') || chars.len() > 3 || (!wildcard && chars.len() < 2) {
+            return Err(invalid());
+        }
+
+        let physics = match chars.first() {
+            Some('?') | None => None,
+            Some(c) => Some(Physics::from_char(*c)?),
+        };
+
+        let band = match chars.get(1) {
+            Some('?') | None => None,
+            Some(c) => Some(
+                c.to_digit(10)
+                    .filter(|b| *b > 0)
+                    .ok_or(Error::InvalidBand(*c))? as u8,
+            ),
+        };
+
+        let attribute = match chars.get(2) {
+            // RINEX 2 observables
+            None if chars.len() == 2 && !wildcard => {
+                if band.is_some_and(|band| !RINEX2_BANDS.contains(&band)) {
+                    return Err(Error::InvalidBand(chars[1]));
+                }
+                Some(Attribute::Absent)
+            }
+            Some('?') | None => None,
+            Some(c) => {
+                let c = c.to_ascii_uppercase();
+                let attributes = band.map(band_attributes).unwrap_or(ATTRIBUTES);
+                if !attributes.contains(c) {
+                    return Err(Error::InvalidAttribute(c));
+                }
+                Some(Attribute::Code(c))
+            }
+        };
+
+        if physics == Some(Physics::PrecisePseudoRange) && attribute != Some(Attribute::Absent) {
+            return Err(Error::UnknownPhysics(chars[0]));
+        }
+
+        Ok(Self {
+            physics,
+            band,
+            attribute,
+        })
+    }
+}
+
+impl std::fmt::Display for Observable {
+    /// Formats this [Observable] so it may be parsed back with [Observable::from_str].
+    /// Trailing wildcards are collapsed, like `L*`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let physics = self.physics.map(|p| p.to_char()).unwrap_or('?');
+        let band = self
+            .band
+            .map(|b| b.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        match (self.band, self.attribute) {
+            (None, None) if self.physics.is_some() => write!(f, "{}*", physics),
+            (None, None) => write!(f, "*"),
+            (_, None) => write!(f, "{}{}?", physics, band),
+            (_, Some(Attribute::Absent)) => write!(f, "{}{}", physics, band),
+            (_, Some(Attribute::Code(code))) => write!(f, "{}{}{}", physics, band, code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn observable_parsing() {
+        let l1c = Observable::from_str("L1C").unwrap();
+        assert_eq!(
+            l1c,
+            Observable {
+                physics: Some(Physics::Phase),
+                band: Some(1),
+                attribute: Some(Attribute::Code('C')),
+            }
+        );
+        assert!(!l1c.is_pattern());
+        assert_eq!(Observable::from_str(" l1c").unwrap(), l1c);
+
+        let p2 = Observable::from_str("P2").unwrap();
+        assert_eq!(p2.physics, Some(Physics::PrecisePseudoRange));
+        assert_eq!(p2.attribute, Some(Attribute::Absent));
+        assert!(!p2.is_pattern());
+
+        let l1 = Observable::from_str("L1?").unwrap();
+        assert_eq!(l1.attribute, None);
+        assert!(l1.is_pattern());
+        assert_ne!(l1, Observable::from_str("L1").unwrap());
+
+        for desc in ["L3I", "C3X", "L4A", "S9X", "C2I", "?5Q"] {
+            assert!(
+                Observable::from_str(desc).is_ok(),
+                "failed to parse \"{}\"",
+                desc
+            );
+        }
+
+        for desc in [
+            "L", "L0C", "L1K", "LX", "X1C", "P1W", "P1?", "L3", "L1C1", "L*1", "*1", "?", "L3C",
+            "C5W", "L4Q", "S9I", "?1K",
+        ] {
+            assert!(
+                Observable::from_str(desc).is_err(),
+                "parsed invalid observable \"{}\"",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    fn observable_patterns() {
+        let l1c = Observable::from_str("L1C").unwrap();
+        let l1w = Observable::from_str("L1W").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l5q = Observable::from_str("L5Q").unwrap();
+        let l1 = Observable::from_str("L1").unwrap();
+
+        for (pattern, matches) in [
+            ("L*", vec![true, true, false, true, true]),
+            ("?1C", vec![true, false, true, false, false]),
+            ("L1", vec![false, false, false, false, true]),
+            ("L1?", vec![true, true, false, false, true]),
+            ("*", vec![true, true, true, true, true]),
+            ("L1C", vec![true, false, false, false, false]),
+        ] {
+            let pattern = Observable::from_str(pattern).unwrap();
+            for (observable, expected) in [l1c, l1w, c1c, l5q, l1].iter().zip(matches) {
+                assert_eq!(
+                    pattern.matches(observable),
+                    expected,
+                    "{} matching {}",
+                    pattern,
+                    observable
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn observable_display() {
+        for (desc, expected) in [
+            ("L1C", "L1C"),
+            ("c2p", "C2P"),
+            ("L1", "L1"),
+            ("L*", "L*"),
+            ("L??", "L*"),
+            ("?1C", "?1C"),
+            ("*", "*"),
+            ("L1*", "L1?"),
+            ("L3?", "L3?"),
+            ("L?", "L?"),
+        ] {
+            let observable = Observable::from_str(desc).unwrap();
+            assert_eq!(observable.to_string(), expected);
+            assert_eq!(Observable::from_str(expected).unwrap(), observable);
+        }
+    }
+}
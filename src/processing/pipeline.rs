@@ -0,0 +1,511 @@
+//! Processing pipelines, to run the same preprocessing ops on many datasets
+use std::{path::Path, str::FromStr};
+use thiserror::Error;
+
+use hifitime::{Epoch, TimeScale};
+
+use crate::processing::{
+    Census, Error as FilterError, Filter, Interpolate, Interval, MaskReport, Preprocessing, Repair,
+    RepairTrait, Smooth, TimeSpan,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [Pipeline] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("i/o error: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("line {line}: {error}")]
+    InvalidStep { line: usize, error: FilterError },
+    #[cfg(feature = "toml")]
+    #[error("toml error: {0}")]
+    TOML(#[from] toml::de::Error),
+}
+
+/// [Step] is one operation of a processing [Pipeline].
+/// It is described like a [Filter], except for `repair:` and `timeshift:` operations:
+/// - `mask:e>10` or `e>10`: [Filter] step
+/// - `repair:zero`: [Repair] step
+/// - `timeshift:GPST`: [TimeScale] transposition step
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// [Filter] step
+    Filter(Filter),
+    /// [Repair] step
+    Repair(Repair),
+    /// Transposition to [TimeScale] step
+    Timeshift(TimeScale),
+}
+
+impl From<Filter> for Step {
+    fn from(filter: Filter) -> Self {
+        Self::Filter(filter)
+    }
+}
+
+impl From<Repair> for Step {
+    fn from(repair: Repair) -> Self {
+        Self::Repair(repair)
+    }
+}
+
+impl std::str::FromStr for Step {
+    type Err = FilterError;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let content = content.trim();
+        if let Some(repair) = content.strip_prefix("repair:") {
+            Ok(Self::Repair(Repair::from_str(repair)?))
+        } else if let Some(timescale) = content.strip_prefix("timeshift:") {
+            let timescale = TimeScale::from_str(timescale.trim())
+                .map_err(|_| FilterError::InvalidTimescale(timescale.trim().to_string()))?;
+            Ok(Self::Timeshift(timescale))
+        } else {
+            Ok(Self::Filter(Filter::from_str(content)?))
+        }
+    }
+}
+
+impl std::fmt::Display for Step {
+    /// Formats this [Step] so it may be parsed back with [Step::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Filter(filter) => write!(f, "{}", filter),
+            Self::Repair(repair) => write!(f, "repair:{}", repair),
+            Self::Timeshift(timescale) => write!(f, "timeshift:{}", timescale),
+        }
+    }
+}
+
+/// [Pipeline] is an ordered list of processing [Step]s, that may be
+/// applied to any [Preprocessing] implementor.
+///
+/// It is usually described by a recipe file, with one [Step] per line,
+/// and comments starting with `#`:
+/// ```text
+/// # retain GPS above 10°
+/// GPS
+/// e>10
+/// decim:30 s
+/// repair:zero
+/// timeshift:GPST
+/// ```
+///
+/// On `toml` crate feature, recipes may also be described in TOML:
+/// ```toml
+/// steps = ["GPS", "e>10", "decim:30 s", "repair:zero", "timeshift:GPST"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pipeline {
+    /// [Step]s, in order of application
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::display_fromstr_vec")
+    )]
+    pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+    /// Copies and returns [Pipeline] with one more [Step]
+    pub fn with_step(&self, step: impl Into<Step>) -> Self {
+        let mut s = self.clone();
+        s.steps.push(step.into());
+        s
+    }
+
+    /// Parses a [Pipeline] from a recipe file. Files with `.toml` extension
+    /// are interpreted as TOML on `toml` crate feature.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        #[cfg(feature = "toml")]
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            return Self::from_toml(&content);
+        }
+
+        Self::from_str(&content)
+    }
+
+    /// Parses a [Pipeline] from a TOML recipe.
+    #[cfg(feature = "toml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn from_toml(content: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Resolves the relative epochs of all masks against given time span,
+    /// see [MaskFilter::resolved](crate::processing::MaskFilter::resolved).
+    pub fn resolved(&self, span: &Interval<Epoch>) -> Self {
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| match step {
+                Step::Filter(filter) => Step::Filter(filter.resolved(span)),
+                step => step.clone(),
+            })
+            .collect();
+        Self { steps }
+    }
+
+    /// Resolves the relative epochs of all masks against the [TimeSpan] of given dataset,
+    /// as it is prior the first [Step].
+    fn resolved_for<T: TimeSpan>(&self, data: &T) -> Self {
+        match data.time_span() {
+            Some(span) => self.resolved(&span),
+            None => self.clone(),
+        }
+    }
+
+    /// Applies all [Step]s in order, to immutable dataset.
+    pub fn apply<T>(&self, data: &T) -> T
+    where
+        T: Preprocessing + Smooth + Interpolate + RepairTrait + TimeSpan + Clone,
+    {
+        let mut data = data.clone();
+        self.apply_mut(&mut data);
+        data
+    }
+
+    /// Applies all [Step]s in order, to mutable dataset.
+    /// Relative epochs are resolved against the [TimeSpan] of the dataset,
+    /// prior the first [Step].
+    pub fn apply_mut<T>(&self, data: &mut T)
+    where
+        T: Preprocessing + Smooth + Interpolate + RepairTrait + TimeSpan,
+    {
+        self.apply_steps(data, |data, filter| data.filter_mut(filter));
+    }
+
+    /// Applies all [Step]s in order, to mutable dataset,
+    /// [Filter] steps being applied by `filter_mut`.
+    fn apply_steps<T>(&self, data: &mut T, mut filter_mut: impl FnMut(&mut T, &Filter))
+    where
+        T: Preprocessing + RepairTrait + TimeSpan,
+    {
+        for step in self.resolved_for(data).steps.iter() {
+            match step {
+                Step::Filter(filter) => filter_mut(data, filter),
+                Step::Repair(repair) => data.repair_mut(*repair),
+                Step::Timeshift(timescale) => data.timeshift_mut(*timescale),
+            }
+        }
+    }
+
+    /// Applies all [Step]s in order, to immutable dataset,
+    /// and reports what the masks removed, in a single [MaskReport].
+    pub fn apply_with_report<T>(&self, data: &T) -> (T, MaskReport)
+    where
+        T: Preprocessing + Smooth + Interpolate + RepairTrait + TimeSpan + Census + Clone,
+    {
+        let mut data = data.clone();
+        let report = self.apply_mut_with_report(&mut data);
+        (data, report)
+    }
+
+    /// Applies all [Step]s in order, to mutable dataset,
+    /// and reports what the masks removed, in a single [MaskReport].
+    pub fn apply_mut_with_report<T>(&self, data: &mut T) -> MaskReport
+    where
+        T: Preprocessing + Smooth + Interpolate + RepairTrait + TimeSpan + Census,
+    {
+        let mut report = MaskReport::default();
+        self.apply_steps(data, |data, filter| {
+            if let Some(mask) = data.filter_mut_with_report(filter) {
+                report.combine(&mask);
+            }
+        });
+        report
+    }
+}
+
+impl std::str::FromStr for Pipeline {
+    type Err = Error;
+    /// Parses a [Pipeline] from a plain-text recipe: one [Step] per line,
+    /// empty lines and comments starting with `#` are ignored.
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let step = Step::from_str(line).map_err(|error| Error::InvalidStep {
+                line: index + 1,
+                error,
+            })?;
+            steps.push(step);
+        }
+        Ok(Self { steps })
+    }
+}
+
+impl std::fmt::Display for Pipeline {
+    /// Formats this [Pipeline] as a plain-text recipe
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        merge::{Error as MergeError, Merge},
+        processing::{
+            DataCensus, Decimate, DecimationFilter, FilterExpr, InterpFilter, Interpolate,
+            MaskFilter, Masking, Smooth, SmoothingFilter, Split, TimeCorrectionError,
+            TimeCorrectionsDB, Timeshift,
+        },
+    };
+    use hifitime::{Duration, Unit};
+
+    /// Dataset that logs the operations applied to it
+    #[derive(Debug, Clone, Default)]
+    struct Log(Vec<String>);
+
+    impl Masking for Log {
+        fn mask(&self, mask: &MaskFilter) -> Self {
+            let mut s = self.clone();
+            s.mask_mut(mask);
+            s
+        }
+        fn mask_mut(&mut self, mask: &MaskFilter) {
+            self.0.push(format!("mask:{}", mask));
+        }
+    }
+
+    impl Decimate for Log {
+        fn decimate(&self, f: &DecimationFilter) -> Self {
+            let mut s = self.clone();
+            s.decimate_mut(f);
+            s
+        }
+        fn decimate_mut(&mut self, f: &DecimationFilter) {
+            self.0.push(format!("decim:{}", f));
+        }
+    }
+
+    impl Smooth for Log {
+        fn smooth(&self, f: &SmoothingFilter) -> Self {
+            let mut s = self.clone();
+            s.smooth_mut(f);
+            s
+        }
+        fn smooth_mut(&mut self, f: &SmoothingFilter) {
+            self.0.push(format!("smooth:{}", f));
+        }
+    }
+
+    impl Interpolate for Log {
+        fn interpolate(&self, f: &InterpFilter) -> Self {
+            let mut s = self.clone();
+            s.interpolate_mut(f);
+            s
+        }
+        fn interpolate_mut(&mut self, f: &InterpFilter) {
+            self.0.push(format!("interp:{}", f));
+        }
+    }
+
+    impl Split for Log {
+        fn split(&self, _: Epoch) -> (Self, Self) {
+            (self.clone(), Self::default())
+        }
+        fn split_mut(&mut self, _: Epoch) -> Self {
+            Self::default()
+        }
+        fn split_even_dt(&self, _: Duration) -> Vec<Self> {
+            vec![self.clone()]
+        }
+    }
+
+    impl Timeshift for Log {
+        fn timeshift(&self, timescale: TimeScale) -> Self {
+            let mut s = self.clone();
+            s.timeshift_mut(timescale);
+            s
+        }
+        fn timeshift_mut(&mut self, timescale: TimeScale) {
+            self.0.push(format!("timeshift:{}", timescale));
+        }
+        fn precise_correction(
+            &self,
+            _: &TimeCorrectionsDB,
+            _: TimeScale,
+        ) -> Result<Self, TimeCorrectionError> {
+            Ok(self.clone())
+        }
+        fn precise_correction_mut(
+            &mut self,
+            _: &TimeCorrectionsDB,
+            _: TimeScale,
+        ) -> Result<(), TimeCorrectionError> {
+            Ok(())
+        }
+    }
+
+    impl Preprocessing for Log {}
+
+    /// Every mask removes one record (out of 10) from the [Log]
+    impl Census for Log {
+        fn census(&self) -> DataCensus {
+            let masks = self.0.iter().filter(|op| op.starts_with("mask:")).count();
+            DataCensus {
+                epochs: 1,
+                records: 10 - masks,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// The [Log] spans 2020-01-01 from midnight to noon,
+    /// and every mask removes its last hour
+    impl TimeSpan for Log {
+        fn time_span(&self) -> Option<Interval<Epoch>> {
+            let masks = self.0.iter().filter(|op| op.starts_with("mask:")).count();
+            let start = Epoch::from_str("2020-01-01T00:00:00 GPST").unwrap();
+            Some(Interval::inclusive(
+                start,
+                start + (12 - masks as i64) as f64 * Unit::Hour,
+            ))
+        }
+    }
+
+    impl Merge for Log {
+        fn merge(&self, rhs: &Self) -> Result<Self, MergeError> {
+            let mut s = self.clone();
+            s.merge_mut(rhs)?;
+            Ok(s)
+        }
+        fn merge_mut(&mut self, rhs: &Self) -> Result<(), MergeError> {
+            self.0.extend(rhs.0.iter().cloned());
+            Ok(())
+        }
+    }
+
+    impl RepairTrait for Log {
+        fn repair(&self, r: Repair) -> Self {
+            let mut s = self.clone();
+            s.repair_mut(r);
+            s
+        }
+        fn repair_mut(&mut self, r: Repair) {
+            self.0.push(format!("repair:{}", r));
+        }
+    }
+
+    const RECIPE: &str = "# retain GPS above 10°
+GPS
+e>10 # elevation mask
+
+decim:30 s
+smooth:hatch:C1C
+repair:zero
+timeshift:GPST
+";
+
+    #[test]
+    fn pipeline_parsing() {
+        let pipeline = Pipeline::from_str(RECIPE).unwrap();
+        assert_eq!(pipeline.steps.len(), 6);
+        assert_eq!(pipeline.steps[4], Step::Repair(Repair::Zero));
+        assert_eq!(pipeline.steps[5], Step::Timeshift(TimeScale::GPST));
+        assert_eq!(Pipeline::from_str(&pipeline.to_string()).unwrap(), pipeline);
+
+        match Pipeline::from_str("GPS\nrepair:none\n") {
+            Err(Error::InvalidStep { line, .. }) => assert_eq!(line, 2),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(Pipeline::from_str("timeshift:XYZ").is_err());
+        assert!(Pipeline::from_str("GPS\nsmooth\n").is_err());
+    }
+
+    #[test]
+    fn pipeline_apply() {
+        let pipeline = Pipeline::from_str(RECIPE).unwrap();
+        let data = pipeline.apply(&Log::default());
+        assert_eq!(
+            data.0,
+            vec![
+                "mask:=GPS",
+                "mask:e>10",
+                "decim:30 s",
+                "smooth:hatch:C1C",
+                "repair:zero",
+                "timeshift:GPST"
+            ]
+        );
+    }
+
+    #[test]
+    fn pipeline_report() {
+        let pipeline = Pipeline::from_str(RECIPE).unwrap();
+        let (data, report) = pipeline.apply_with_report(&Log::default());
+        assert_eq!(data.0.len(), 6);
+        assert_eq!(
+            report
+                .masks
+                .iter()
+                .map(|mask| mask.to_string())
+                .collect::<Vec<_>>(),
+            vec!["=GPS", "e>10"]
+        );
+        assert_eq!(report.before.records, 10);
+        assert_eq!(report.after.records, 8);
+        assert_eq!(report.removed.records, 2);
+        assert_eq!(report.removed.epochs, 0);
+        assert_eq!(report.removed_ratio(), 0.2);
+    }
+
+    #[test]
+    fn relative_steps() {
+        // relative epochs are resolved against the span prior the first step
+        let span = Log::default().time_span().unwrap();
+        let last = MaskFilter::from_str("last:2h").unwrap().resolved(&span);
+        assert_eq!(
+            last,
+            MaskFilter::from_str("in [2020-01-01T10:00:00 GPST, 2020-01-01T12:00:00 GPST]")
+                .unwrap()
+        );
+        let expected = vec!["mask:e>10".to_string(), format!("mask:{}", last)];
+
+        let pipeline = Pipeline::from_str("e>10\nlast:2h\n").unwrap();
+        assert_eq!(pipeline.apply(&Log::default()).0, expected);
+
+        let (data, report) = pipeline.apply_with_report(&Log::default());
+        assert_eq!(data.0, expected);
+        assert_eq!(report.masks[1], last);
+
+        let expr = FilterExpr::from_str("e>10 & last:2h").unwrap();
+        let data = Log::default().filter_expr(&expr).unwrap();
+        assert_eq!(data.0, expected);
+
+        let mut data = Log::default();
+        data.filter_expr_mut(&expr).unwrap();
+        assert_eq!(data.0, expected);
+
+        let mut data = Log::default();
+        let report = data.filter_expr_mut_with_report(&expr).unwrap();
+        assert_eq!(data.0, expected);
+        assert_eq!(report.masks[1], last);
+        assert_eq!(report.before.records, 10);
+        assert_eq!(report.after.records, 8);
+        assert_eq!(report.removed.records, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn pipeline_toml() {
+        let pipeline = Pipeline::from_toml(
+            r#"steps = ["GPS", "e>10", "decim:30 s", "smooth:hatch:C1C", "repair:zero", "timeshift:GPST"]"#,
+        )
+        .unwrap();
+        assert_eq!(pipeline, Pipeline::from_str(RECIPE).unwrap());
+    }
+}
@@ -0,0 +1,396 @@
+//! Geographic regions, to retain data collected at given locations
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::processing::Interval;
+
+/// Mean Earth radius, in meters, used in great-circle distances
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// [Region] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid region \"{0}\"")]
+    InvalidRegion(String),
+    #[error("unknown region shape \"{0}\"")]
+    UnknownShape(String),
+    #[error("invalid coordinates \"{0}\"")]
+    InvalidCoordinates(String),
+    #[error("invalid radius \"{0}\"")]
+    InvalidRadius(String),
+    #[error("polygons require at least three vertices")]
+    DegeneratedPolygon,
+}
+
+/// [GeodeticPosition] of a receiver, to be tested against a [Region].
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+pub struct GeodeticPosition {
+    /// Latitude in degrees, positive northward
+    pub latitude: f64,
+    /// Longitude in degrees, positive eastward
+    pub longitude: f64,
+    /// Altitude above the ellipsoid, in meters
+    pub altitude: f64,
+}
+
+impl GeodeticPosition {
+    /// Builds a new [GeodeticPosition] from latitude and longitude (in degrees)
+    /// and altitude (in meters).
+    pub fn new(latitude: f64, longitude: f64, altitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            altitude,
+        }
+    }
+
+    /// Returns the great-circle distance to given [GeodeticPosition], in meters.
+    /// Altitudes are not accounted for.
+    pub fn distance(&self, rhs: &Self) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), rhs.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (rhs.longitude - self.longitude).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+    }
+}
+
+/// Geographic [Region], described as `shape(parameters)`:
+/// - `box(lat_min,lon_min,lat_max,lon_max)`: latitude and longitude bounding box, in degrees.
+///   The box crosses the antimeridian when `lon_min > lon_max`.
+/// - `circle(lat,lon,radius)`: great-circle radius around a point, the radius being
+///   expressed in meters, unless suffixed by `m` or `km`, like `circle(48.8,2.3,5km)`.
+/// - `polygon(lat1,lon1,lat2,lon2,lat3,lon3,..)`: arbitrary polygon, described by
+///   at least three vertices, in degrees. Edges follow the shortest way in longitude,
+///   so polygons may cross the antimeridian, but may not enclose a pole.
+/// - `altitude(interval)`: altitude band, in meters, like `altitude(0..500)`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum Region {
+    /// Latitude and longitude bounding box, in degrees
+    BoundingBox {
+        min_latitude: f64,
+        min_longitude: f64,
+        max_latitude: f64,
+        max_longitude: f64,
+    },
+    /// Great-circle radius (in meters) around a point (in degrees)
+    Circle {
+        latitude: f64,
+        longitude: f64,
+        radius: f64,
+    },
+    /// Polygon, described by its (latitude, longitude) vertices in degrees
+    Polygon(Vec<(f64, f64)>),
+    /// Altitude band, in meters
+    Altitude(Interval<f64>),
+}
+
+impl Region {
+    /// Returns true if given [GeodeticPosition] lies within this [Region].
+    pub fn contains(&self, position: &GeodeticPosition) -> bool {
+        match self {
+            Self::BoundingBox {
+                min_latitude,
+                min_longitude,
+                max_latitude,
+                max_longitude,
+            } => {
+                let latitude =
+                    position.latitude >= *min_latitude && position.latitude <= *max_latitude;
+                let longitude = if min_longitude <= max_longitude {
+                    position.longitude >= *min_longitude && position.longitude <= *max_longitude
+                } else {
+                    position.longitude >= *min_longitude || position.longitude <= *max_longitude
+                };
+                latitude && longitude
+            }
+            Self::Circle {
+                latitude,
+                longitude,
+                radius,
+            } => {
+                let center = GeodeticPosition::new(*latitude, *longitude, 0.0);
+                center.distance(position) <= *radius
+            }
+            Self::Polygon(vertices) => {
+                if vertices.len() < 3 {
+                    return false;
+                }
+                // unwrap the longitudes, so edges crossing the antimeridian remain continuous
+                let mut unwrapped = Vec::with_capacity(vertices.len());
+                let mut longitude = vertices[0].1;
+                for (index, (latitude, lon)) in vertices.iter().enumerate() {
+                    if index > 0 {
+                        longitude +=
+                            (lon - vertices[index - 1].1 + 180.0).rem_euclid(360.0) - 180.0;
+                    }
+                    unwrapped.push((*latitude, longitude));
+                }
+                [-360.0, 0.0, 360.0].iter().any(|shift| {
+                    // ray casting, in the (longitude, latitude) plane
+                    let (y, x) = (position.latitude, position.longitude + shift);
+                    let mut inside = false;
+                    let mut previous = unwrapped[unwrapped.len() - 1];
+                    for vertex in unwrapped.iter() {
+                        let ((yi, xi), (yj, xj)) = (*vertex, previous);
+                        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                            inside = !inside;
+                        }
+                        previous = *vertex;
+                    }
+                    inside
+                })
+            }
+            Self::Altitude(interval) => interval.contains(&position.altitude),
+        }
+    }
+}
+
+/// Parses a comma separated list of coordinates
+fn parse_coordinates(content: &str) -> Result<Vec<f64>, Error> {
+    content
+        .split(',')
+        .map(|c| {
+            f64::from_str(c.trim()).map_err(|_| Error::InvalidCoordinates(c.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Verifies given latitude and longitude (in degrees)
+fn checked_coordinates(latitude: f64, longitude: f64) -> Result<(f64, f64), Error> {
+    if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) {
+        Ok((latitude, longitude))
+    } else {
+        Err(Error::InvalidCoordinates(format!(
+            "{},{}",
+            latitude, longitude
+        )))
+    }
+}
+
+/// Parses a radius in meters, possibly suffixed by `m` or `km`
+fn parse_radius(content: &str) -> Result<f64, Error> {
+    let c = content.trim();
+    let (value, scaling) = match c.strip_suffix("km") {
+        Some(value) => (value, 1000.0),
+        None => (c.strip_suffix('m').unwrap_or(c), 1.0),
+    };
+    match f64::from_str(value.trim()) {
+        Ok(radius) if radius > 0.0 => Ok(radius * scaling),
+        _ => Err(Error::InvalidRadius(c.to_string())),
+    }
+}
+
+impl FromStr for Region {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let (shape, parameters) = c
+            .strip_suffix(')')
+            .and_then(|c| c.split_once('('))
+            .ok_or(Error::InvalidRegion(c.to_string()))?;
+
+        match shape.trim().to_ascii_lowercase().as_str() {
+            "box" => match parse_coordinates(parameters)?[..] {
+                [lat_min, lon_min, lat_max, lon_max] => {
+                    let (min_latitude, min_longitude) = checked_coordinates(lat_min, lon_min)?;
+                    let (max_latitude, max_longitude) = checked_coordinates(lat_max, lon_max)?;
+                    if min_latitude > max_latitude {
+                        return Err(Error::InvalidRegion(c.to_string()));
+                    }
+                    Ok(Self::BoundingBox {
+                        min_latitude,
+                        min_longitude,
+                        max_latitude,
+                        max_longitude,
+                    })
+                }
+                _ => Err(Error::InvalidRegion(c.to_string())),
+            },
+            "circle" => {
+                let (center, radius) = parameters
+                    .rsplit_once(',')
+                    .ok_or(Error::InvalidRegion(c.to_string()))?;
+                match parse_coordinates(center)?[..] {
+                    [latitude, longitude] => {
+                        let (latitude, longitude) = checked_coordinates(latitude, longitude)?;
+                        Ok(Self::Circle {
+                            latitude,
+                            longitude,
+                            radius: parse_radius(radius)?,
+                        })
+                    }
+                    _ => Err(Error::InvalidRegion(c.to_string())),
+                }
+            }
+            "polygon" => {
+                let coordinates = parse_coordinates(parameters)?;
+                if coordinates.len() % 2 != 0 {
+                    return Err(Error::InvalidRegion(c.to_string()));
+                }
+                let vertices = coordinates
+                    .chunks(2)
+                    .map(|pair| checked_coordinates(pair[0], pair[1]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if vertices.len() < 3 {
+                    return Err(Error::DegeneratedPolygon);
+                }
+                Ok(Self::Polygon(vertices))
+            }
+            "altitude" => Interval::<f64>::from_str(parameters)
+                .map(Self::Altitude)
+                .map_err(|_| Error::InvalidRegion(c.to_string())),
+            shape => Err(Error::UnknownShape(shape.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Region {
+    /// Formats this [Region] so it may be parsed back with [Region::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::BoundingBox {
+                min_latitude,
+                min_longitude,
+                max_latitude,
+                max_longitude,
+            } => write!(
+                f,
+                "box({},{},{},{})",
+                min_latitude, min_longitude, max_latitude, max_longitude
+            ),
+            Self::Circle {
+                latitude,
+                longitude,
+                radius,
+            } => {
+                if radius % 1000.0 == 0.0 {
+                    write!(
+                        f,
+                        "circle({},{},{}km)",
+                        latitude,
+                        longitude,
+                        radius / 1000.0
+                    )
+                } else {
+                    write!(f, "circle({},{},{}m)", latitude, longitude, radius)
+                }
+            }
+            Self::Polygon(vertices) => {
+                let vertices = vertices
+                    .iter()
+                    .map(|(latitude, longitude)| format!("{},{}", latitude, longitude))
+                    .collect::<Vec<_>>();
+                write!(f, "polygon({})", vertices.join(","))
+            }
+            Self::Altitude(interval) => write!(f, "altitude({})", interval),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn region_parsing() {
+        for (desc, expected) in [
+            (
+                "circle(48.8,2.3,5km)",
+                Region::Circle {
+                    latitude: 48.8,
+                    longitude: 2.3,
+                    radius: 5000.0,
+                },
+            ),
+            (
+                "circle(48.8, 2.3, 250)",
+                Region::Circle {
+                    latitude: 48.8,
+                    longitude: 2.3,
+                    radius: 250.0,
+                },
+            ),
+            (
+                "box(40,-5,50,10)",
+                Region::BoundingBox {
+                    min_latitude: 40.0,
+                    min_longitude: -5.0,
+                    max_latitude: 50.0,
+                    max_longitude: 10.0,
+                },
+            ),
+            (
+                "polygon(0,0,0,10,10,10)",
+                Region::Polygon(vec![(0.0, 0.0), (0.0, 10.0), (10.0, 10.0)]),
+            ),
+            (
+                "altitude(0..500)",
+                Region::Altitude(Interval::new(0.0, 500.0)),
+            ),
+        ] {
+            let region = Region::from_str(desc).unwrap();
+            assert_eq!(region, expected, "for \"{}\"", desc);
+            assert_eq!(Region::from_str(&region.to_string()).unwrap(), region);
+        }
+
+        for desc in [
+            "circle(48.8,2.3)",
+            "circle(48.8,2.3,-5km)",
+            "circle(98.8,2.3,5km)",
+            "box(50,-5,40,10)",
+            "polygon(0,0,0,10)",
+            "polygon(0,0,0,10,10)",
+            "square(0,0,1)",
+            "circle(48.8,2.3,5km",
+        ] {
+            assert!(
+                Region::from_str(desc).is_err(),
+                "parsed invalid \"{}\"",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    fn region_contains() {
+        let paris = GeodeticPosition::new(48.8566, 2.3522, 35.0);
+        let versailles = GeodeticPosition::new(48.8049, 2.1204, 130.0);
+        let fiji = GeodeticPosition::new(-17.7, 178.0, 10.0);
+
+        let circle = Region::from_str("circle(48.8566,2.3522,5km)").unwrap();
+        assert!(circle.contains(&paris));
+        assert!(!circle.contains(&versailles));
+        assert!(
+            Region::from_str("circle(48.8566,2.3522,20km)")
+                .unwrap()
+                .contains(&versailles)
+        );
+
+        let bbox = Region::from_str("box(40,-5,50,10)").unwrap();
+        assert!(bbox.contains(&paris));
+        assert!(!bbox.contains(&fiji));
+
+        let antimeridian = Region::from_str("box(-20,170,-10,-170)").unwrap();
+        assert!(antimeridian.contains(&fiji));
+        assert!(!antimeridian.contains(&paris));
+
+        let triangle = Region::from_str("polygon(48,2,50,2,48,4)").unwrap();
+        assert!(triangle.contains(&paris));
+        assert!(!triangle.contains(&GeodeticPosition::new(49.9, 3.9, 0.0)));
+
+        let pacific = Region::from_str("polygon(-20,170,-20,-170,-10,-170,-10,170)").unwrap();
+        assert!(pacific.contains(&fiji));
+        assert!(pacific.contains(&GeodeticPosition::new(-15.0, -175.0, 0.0)));
+        assert!(!pacific.contains(&paris));
+        assert!(!pacific.contains(&GeodeticPosition::new(-15.0, 0.0, 0.0)));
+
+        let segment = Region::Polygon(vec![(48.0, 2.0), (50.0, 4.0)]);
+        assert!(!segment.contains(&paris));
+        assert!(!Region::Polygon(vec![]).contains(&paris));
+
+        let altitude = Region::from_str("altitude(0..100)").unwrap();
+        assert!(altitude.contains(&paris));
+        assert!(!altitude.contains(&versailles));
+    }
+}
@@ -0,0 +1,272 @@
+//! Epochs described relative to the dataset time span
+use std::str::FromStr;
+use thiserror::Error;
+
+use hifitime::{Duration, Epoch};
+
+use crate::processing::{Interval, MaskFilter};
+
+/// Relative epochs parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid relative epoch \"{0}\"")]
+    InvalidRelativeEpoch(String),
+    #[error("invalid duration \"{0}\"")]
+    InvalidDuration(String),
+}
+
+/// [TimeSpan] is implemented by datasets that may report their time span,
+/// so relative epochs (like `end-2h`) may be resolved into absolute epochs.
+/// Datasets that do not report their time span may implement it with
+/// the default [TimeSpan::time_span]: relative epochs then remain unresolved.
+pub trait TimeSpan {
+    /// Returns the time span of this dataset, from first to last [Epoch] (both included),
+    /// or None when the dataset is empty or does not report its time span.
+    fn time_span(&self) -> Option<Interval<Epoch>> {
+        None
+    }
+
+    /// Resolves the relative epochs of this [MaskFilter] against our [TimeSpan].
+    /// The [MaskFilter] is returned as is, when it does not describe relative epochs
+    /// or this dataset is empty.
+    fn resolve(&self, mask: &MaskFilter) -> MaskFilter {
+        match self.time_span() {
+            Some(span) => mask.resolved(&span),
+            None => mask.clone(),
+        }
+    }
+}
+
+/// [TimeAnchor] of a [RelativeEpoch]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeAnchor {
+    /// First epoch of the dataset
+    Start,
+    /// Last epoch of the dataset
+    End,
+}
+
+impl TimeAnchor {
+    /// Returns the keyword of this [TimeAnchor]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::End => "end",
+        }
+    }
+
+    /// Returns the [Epoch] this [TimeAnchor] designates, within given time span
+    pub fn epoch(&self, span: &Interval<Epoch>) -> Epoch {
+        match self {
+            Self::Start => span.start,
+            Self::End => span.end,
+        }
+    }
+}
+
+/// Parses a [Duration], the unit may be attached to the value (like `30min`)
+fn parse_duration(content: &str) -> Result<Duration, Error> {
+    let c = content.trim();
+    let split = c.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(c.len());
+    let (value, unit) = c.split_at(split);
+    if value.trim().is_empty() || unit.is_empty() {
+        return Err(Error::InvalidDuration(c.to_string()));
+    }
+    Duration::from_str(&format!("{} {}", value.trim(), unit))
+        .map_err(|_| Error::InvalidDuration(c.to_string()))
+}
+
+/// [RelativeEpoch] is an [Epoch] described relative to the start or the end
+/// of the dataset, like `start+30min` or `end-2h`.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct RelativeEpoch {
+    /// [TimeAnchor] this epoch is relative to
+    pub anchor: TimeAnchor,
+    /// Signed offset to the [TimeAnchor]
+    pub offset: Duration,
+}
+
+impl RelativeEpoch {
+    /// Resolves this [RelativeEpoch] within given time span
+    pub fn resolve(&self, span: &Interval<Epoch>) -> Epoch {
+        self.anchor.epoch(span) + self.offset
+    }
+}
+
+impl FromStr for RelativeEpoch {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let lowercase = c.to_ascii_lowercase();
+        let (anchor, rest) = if let Some(rest) = lowercase.strip_prefix("start") {
+            (TimeAnchor::Start, rest)
+        } else if let Some(rest) = lowercase.strip_prefix("end") {
+            (TimeAnchor::End, rest)
+        } else {
+            return Err(Error::InvalidRelativeEpoch(c.to_string()));
+        };
+
+        let rest = rest.trim_start();
+        let offset = if rest.is_empty() {
+            Duration::ZERO
+        } else if let Some(dt) = rest.strip_prefix('+') {
+            parse_duration(dt)?
+        } else if let Some(dt) = rest.strip_prefix('-') {
+            -parse_duration(dt)?
+        } else {
+            return Err(Error::InvalidRelativeEpoch(c.to_string()));
+        };
+
+        Ok(Self { anchor, offset })
+    }
+}
+
+impl std::fmt::Display for RelativeEpoch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.offset == Duration::ZERO {
+            write!(f, "{}", self.anchor.name())
+        } else if self.offset < Duration::ZERO {
+            write!(f, "{}-{}", self.anchor.name(), self.offset.abs())
+        } else {
+            write!(f, "{}+{}", self.anchor.name(), self.offset)
+        }
+    }
+}
+
+/// [RelativeSpan] is a period at the start or the end of the dataset,
+/// like `first:10min` or `last:2h`.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub enum RelativeSpan {
+    /// Period starting at the first epoch
+    First(Duration),
+    /// Period ending at the last epoch
+    Last(Duration),
+}
+
+impl RelativeSpan {
+    /// Resolves this [RelativeSpan] within given time span.
+    /// The resulting [Interval] includes both ends.
+    pub fn resolve(&self, span: &Interval<Epoch>) -> Interval<Epoch> {
+        match self {
+            Self::First(dt) => Interval::inclusive(span.start, span.start + *dt),
+            Self::Last(dt) => Interval::inclusive(span.end - *dt, span.end),
+        }
+    }
+}
+
+impl FromStr for RelativeSpan {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let (keyword, dt) = c
+            .split_once(':')
+            .ok_or(Error::InvalidRelativeEpoch(c.to_string()))?;
+        let dt = parse_duration(dt)?;
+        if dt < Duration::ZERO {
+            return Err(Error::InvalidDuration(c.to_string()));
+        }
+        match keyword.trim().to_ascii_lowercase().as_str() {
+            "first" => Ok(Self::First(dt)),
+            "last" => Ok(Self::Last(dt)),
+            _ => Err(Error::InvalidRelativeEpoch(c.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for RelativeSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::First(dt) => write!(f, "first:{}", dt),
+            Self::Last(dt) => write!(f, "last:{}", dt),
+        }
+    }
+}
+
+/// Returns true if this description is a relative epoch or span
+pub(crate) fn is_relative(content: &str) -> bool {
+    let lowercase = content.trim().to_ascii_lowercase();
+    ["start", "end"].iter().any(|anchor| {
+        lowercase
+            .strip_prefix(anchor)
+            .is_some_and(|rest| rest.is_empty() || rest.trim_start().starts_with(['+', '-']))
+    }) || lowercase.starts_with("first:")
+        || lowercase.starts_with("last:")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hifitime::Unit;
+
+    #[test]
+    fn relative_epochs() {
+        let span = Interval::inclusive(
+            Epoch::from_str("2020-01-01T00:00:00 GPST").unwrap(),
+            Epoch::from_str("2020-01-01T12:00:00 GPST").unwrap(),
+        );
+
+        for (desc, anchor, offset, resolved) in [
+            (
+                "start",
+                TimeAnchor::Start,
+                Duration::ZERO,
+                "2020-01-01T00:00:00 GPST",
+            ),
+            (
+                "start+30min",
+                TimeAnchor::Start,
+                30.0 * Unit::Minute,
+                "2020-01-01T00:30:00 GPST",
+            ),
+            (
+                "END - 2 h",
+                TimeAnchor::End,
+                -2.0 * Unit::Hour,
+                "2020-01-01T10:00:00 GPST",
+            ),
+        ] {
+            let epoch = RelativeEpoch::from_str(desc).unwrap();
+            assert_eq!(epoch, RelativeEpoch { anchor, offset }, "for \"{}\"", desc);
+            assert_eq!(epoch.resolve(&span), Epoch::from_str(resolved).unwrap());
+            assert_eq!(RelativeEpoch::from_str(&epoch.to_string()).unwrap(), epoch);
+        }
+
+        let last = RelativeSpan::from_str("last:2h").unwrap();
+        assert_eq!(last, RelativeSpan::Last(2.0 * Unit::Hour));
+        assert_eq!(
+            last.resolve(&span),
+            Interval::inclusive(
+                Epoch::from_str("2020-01-01T10:00:00 GPST").unwrap(),
+                span.end
+            )
+        );
+        let first = RelativeSpan::from_str("first: 10 min").unwrap();
+        assert_eq!(first, RelativeSpan::First(10.0 * Unit::Minute));
+        assert_eq!(RelativeSpan::from_str(&first.to_string()).unwrap(), first);
+
+        for desc in [
+            "start+",
+            "end*2h",
+            "start+2",
+            "ending",
+            "last:",
+            "latest:2h",
+        ] {
+            assert!(
+                RelativeEpoch::from_str(desc).is_err() && RelativeSpan::from_str(desc).is_err(),
+                "parsed \"{}\"",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    fn default_time_span() {
+        struct Unspanned;
+        impl TimeSpan for Unspanned {}
+
+        let mask = MaskFilter::from_str("last:2h").unwrap();
+        assert_eq!(Unspanned.time_span(), None);
+        assert_eq!(Unspanned.resolve(&mask), mask);
+    }
+}
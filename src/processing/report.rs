@@ -0,0 +1,217 @@
+//! Masking reports, to audit what masks removed from a dataset
+use std::collections::BTreeMap;
+
+use gnss_rs::prelude::{Constellation, SV};
+
+use crate::processing::{MaskFilter, Observable};
+
+/// [Census] is implemented by datasets that may count their content,
+/// so masking operations may be reported (see [MaskReport]).
+pub trait Census {
+    /// Counts the content of this dataset
+    fn census(&self) -> DataCensus;
+}
+
+/// [DataCensus] counts the content of a dataset.
+/// A record is one value (like one observation), described
+/// by its [SV] and [Observable], at one epoch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataCensus {
+    /// Number of epochs
+    pub epochs: usize,
+    /// Total number of records
+    pub records: usize,
+    /// Number of records per [SV]
+    pub sv: BTreeMap<SV, usize>,
+    /// Number of records per [Constellation]
+    pub constellations: BTreeMap<Constellation, usize>,
+    /// Number of records per [Observable]
+    pub observables: BTreeMap<Observable, usize>,
+}
+
+/// Subtracts two subset counts, dropping the subsets that cancel out
+fn subtracted<K: Ord + Copy>(
+    lhs: &BTreeMap<K, usize>,
+    rhs: &BTreeMap<K, usize>,
+) -> BTreeMap<K, usize> {
+    lhs.iter()
+        .map(|(key, count)| (*key, count.saturating_sub(*rhs.get(key).unwrap_or(&0))))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// Sums two subset counts
+fn summed<K: Ord + Copy>(lhs: &mut BTreeMap<K, usize>, rhs: &BTreeMap<K, usize>) {
+    for (key, count) in rhs.iter() {
+        *lhs.entry(*key).or_default() += count;
+    }
+}
+
+impl DataCensus {
+    /// Adds one record, of this [SV] and [Observable], to this [DataCensus].
+    /// Epochs are counted separately.
+    pub fn add_record(&mut self, sv: SV, observable: Observable) {
+        self.records += 1;
+        *self.sv.entry(sv).or_default() += 1;
+        *self.constellations.entry(sv.constellation).or_default() += 1;
+        *self.observables.entry(observable).or_default() += 1;
+    }
+
+    /// Returns what this [DataCensus] counts in excess of given [DataCensus],
+    /// typically what a mask removed when `self` was counted prior masking.
+    pub fn difference(&self, rhs: &Self) -> Self {
+        Self {
+            epochs: self.epochs.saturating_sub(rhs.epochs),
+            records: self.records.saturating_sub(rhs.records),
+            sv: subtracted(&self.sv, &rhs.sv),
+            constellations: subtracted(&self.constellations, &rhs.constellations),
+            observables: subtracted(&self.observables, &rhs.observables),
+        }
+    }
+
+    /// Accumulates given [DataCensus] into this one
+    pub fn accumulate(&mut self, rhs: &Self) {
+        self.epochs += rhs.epochs;
+        self.records += rhs.records;
+        summed(&mut self.sv, &rhs.sv);
+        summed(&mut self.constellations, &rhs.constellations);
+        summed(&mut self.observables, &rhs.observables);
+    }
+}
+
+/// [MaskReport] describes what one or several [MaskFilter]s removed from a dataset.
+/// Reports of successive masks are combined with [MaskReport::combine]:
+/// `before` is then counted prior the first mask, `after` past the last one,
+/// while `removed` only accounts for the masks (other operations, like decimation,
+/// may take place in between).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MaskReport {
+    /// [MaskFilter]s, in order of application
+    pub masks: Vec<MaskFilter>,
+    /// [DataCensus] prior masking
+    pub before: DataCensus,
+    /// [DataCensus] after masking
+    pub after: DataCensus,
+    /// [DataCensus] of the data removed by the masks
+    pub removed: DataCensus,
+}
+
+impl MaskReport {
+    /// Builds the [MaskReport] of one [MaskFilter], from the [DataCensus]
+    /// prior and after its application.
+    pub fn new(mask: &MaskFilter, before: DataCensus, after: DataCensus) -> Self {
+        Self {
+            masks: vec![mask.clone()],
+            removed: before.difference(&after),
+            before,
+            after,
+        }
+    }
+
+    /// Combines the [MaskReport] of a following mask into this one.
+    pub fn combine(&mut self, rhs: &Self) {
+        if self.masks.is_empty() {
+            self.before = rhs.before.clone();
+        }
+        self.masks.extend(rhs.masks.iter().cloned());
+        self.after = rhs.after.clone();
+        self.removed.accumulate(&rhs.removed);
+    }
+
+    /// Returns the ratio (0 to 1) of records removed by the masks
+    pub fn removed_ratio(&self) -> f64 {
+        if self.before.records == 0 {
+            0.0
+        } else {
+            self.removed.records as f64 / self.before.records as f64
+        }
+    }
+}
+
+impl std::fmt::Display for MaskReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let masks = self
+            .masks
+            .iter()
+            .map(|mask| mask.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "{}: {} records removed ({:.1}%), {} epochs removed",
+            masks,
+            self.removed.records,
+            self.removed_ratio() * 100.0,
+            self.removed.epochs,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn mask_reports() {
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let g01 = SV::new(Constellation::GPS, 1);
+        let e01 = SV::new(Constellation::Galileo, 1);
+
+        let mut before = DataCensus {
+            epochs: 2,
+            ..Default::default()
+        };
+        for sv in [g01, e01] {
+            for observable in [c1c, l1c, c1c, l1c] {
+                before.add_record(sv, observable);
+            }
+        }
+
+        let mut after = DataCensus {
+            epochs: 2,
+            ..Default::default()
+        };
+        for observable in [c1c, l1c, c1c, l1c] {
+            after.add_record(g01, observable);
+        }
+
+        let gps = MaskFilter::from_str("GPS").unwrap();
+        let mut report = MaskReport::new(&gps, before.clone(), after.clone());
+        assert_eq!(report.removed.records, 4);
+        assert_eq!(report.removed.epochs, 0);
+        assert_eq!(report.removed.sv, BTreeMap::from([(e01, 4)]));
+        assert_eq!(
+            report.removed.constellations,
+            BTreeMap::from([(Constellation::Galileo, 4)])
+        );
+        assert_eq!(
+            report.removed.observables,
+            BTreeMap::from([(c1c, 2), (l1c, 2)])
+        );
+        assert_eq!(report.removed_ratio(), 0.5);
+
+        let mut last = DataCensus {
+            epochs: 1,
+            ..Default::default()
+        };
+        last.add_record(g01, c1c);
+
+        let elevation = MaskFilter::from_str("e>10").unwrap();
+        report.combine(&MaskReport::new(&elevation, after, last.clone()));
+        assert_eq!(report.masks, vec![gps, elevation]);
+        assert_eq!(report.before, before);
+        assert_eq!(report.after, last);
+        assert_eq!(report.removed.records, 7);
+        assert_eq!(report.removed.epochs, 1);
+        assert_eq!(
+            report.removed.observables,
+            BTreeMap::from([(c1c, 3), (l1c, 4)])
+        );
+
+        let mut combined = MaskReport::default();
+        combined.combine(&report);
+        assert_eq!(combined, report);
+    }
+}
@@ -0,0 +1,198 @@
+//! Satellite metadata: orbit class and block (generation)
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+use gnss_rs::prelude::SV;
+
+/// Embedded satellite metadata table, see [SatelliteDB::default]
+const SATELLITES: &str = include_str!("../../data/satellites.txt");
+
+/// Satellite metadata errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown orbit class \"{0}\"")]
+    UnknownOrbitClass(String),
+    #[error("invalid block \"{0}\"")]
+    InvalidBlock(String),
+    #[error("invalid satellite metadata entry (line {0})")]
+    InvalidEntry(usize),
+}
+
+/// [OrbitClass] of a satellite
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OrbitClass {
+    /// Geostationary orbit
+    GEO,
+    /// Inclined geosynchronous orbit
+    IGSO,
+    /// Medium Earth orbit
+    MEO,
+    /// Low Earth orbit
+    LEO,
+}
+
+impl OrbitClass {
+    /// All known [OrbitClass]es
+    pub const ALL: [Self; 4] = [Self::GEO, Self::IGSO, Self::MEO, Self::LEO];
+}
+
+impl FromStr for OrbitClass {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        Self::ALL
+            .iter()
+            .find(|class| class.to_string().eq_ignore_ascii_case(c))
+            .copied()
+            .ok_or(Error::UnknownOrbitClass(c.to_string()))
+    }
+}
+
+impl std::fmt::Display for OrbitClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Parses a satellite block (or generation) name, like `IIIA` or `FOC`.
+/// Block names are case insensitive and formatted in uppercase.
+pub(crate) fn parse_block(content: &str) -> Result<String, Error> {
+    let c = content.trim();
+    let valid = !c.is_empty()
+        && c.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '+');
+    if valid {
+        Ok(c.to_ascii_uppercase())
+    } else {
+        Err(Error::InvalidBlock(c.to_string()))
+    }
+}
+
+/// [SatelliteInfo] describes a satellite in the [SatelliteDB]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatelliteInfo {
+    /// [OrbitClass] of this satellite
+    pub orbit: OrbitClass,
+    /// Block (or generation), like `IIIA` or `FOC`
+    pub block: String,
+}
+
+/// [SatelliteDB] is a satellite metadata table keyed on [SV], used to resolve
+/// orbit class and block masks. [SatelliteDB::default] returns our embedded table,
+/// which you may update or override, when satellites are launched or decommissioned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatelliteDB {
+    /// [SatelliteInfo] per [SV]
+    satellites: BTreeMap<SV, SatelliteInfo>,
+}
+
+impl Default for SatelliteDB {
+    /// Builds the embedded satellite metadata table
+    fn default() -> Self {
+        let mut db = Self::empty();
+        db.update(SATELLITES)
+            .expect("embedded satellite metadata table is invalid");
+        db
+    }
+}
+
+impl SatelliteDB {
+    /// Builds an empty [SatelliteDB]
+    pub fn empty() -> Self {
+        Self {
+            satellites: Default::default(),
+        }
+    }
+
+    /// Adds (or replaces) the [SatelliteInfo] of this [SV]
+    pub fn insert(&mut self, sv: SV, info: SatelliteInfo) {
+        self.satellites.insert(sv, info);
+    }
+
+    /// Discards this [SV] from the database, when decommissioned.
+    pub fn remove(&mut self, sv: &SV) {
+        self.satellites.remove(sv);
+    }
+
+    /// Updates this [SatelliteDB] with a table described like our embedded table:
+    /// one satellite per line, like `G04 MEO IIIA`, `#` starting a comment.
+    /// Listed satellites replace possible existing entries.
+    pub fn update(&mut self, table: &str) -> Result<(), Error> {
+        for (nth, line) in table.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || Error::InvalidEntry(nth + 1);
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [sv, orbit, block] => {
+                    let sv = SV::from_str(sv).map_err(|_| invalid())?;
+                    let orbit = OrbitClass::from_str(orbit).map_err(|_| invalid())?;
+                    let block = parse_block(block).map_err(|_| invalid())?;
+                    self.insert(sv, SatelliteInfo { orbit, block });
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the [SatelliteInfo] of this [SV], if known
+    pub fn info(&self, sv: &SV) -> Option<&SatelliteInfo> {
+        self.satellites.get(sv)
+    }
+
+    /// Returns the [OrbitClass] of this [SV], if known.
+    /// SBAS vehicles are geostationary, unless described otherwise.
+    pub fn orbit(&self, sv: &SV) -> Option<OrbitClass> {
+        match self.info(sv) {
+            Some(info) => Some(info.orbit),
+            None if sv.constellation.is_sbas() => Some(OrbitClass::GEO),
+            None => None,
+        }
+    }
+
+    /// Returns the block (or generation) of this [SV], if known
+    pub fn block(&self, sv: &SV) -> Option<&str> {
+        self.info(sv).map(|info| info.block.as_str())
+    }
+
+    /// Returns an iterator over all known [SV]s and their [SatelliteInfo]
+    pub fn iter(&self) -> impl Iterator<Item = (&SV, &SatelliteInfo)> {
+        self.satellites.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gnss_rs::prelude::Constellation;
+
+    #[test]
+    fn satellite_db() {
+        let mut db = SatelliteDB::default();
+        let c01 = SV::new(Constellation::BeiDou, 1);
+        let c38 = SV::new(Constellation::BeiDou, 38);
+        let e11 = SV::new(Constellation::Galileo, 11);
+        let g04 = SV::new(Constellation::GPS, 4);
+
+        assert_eq!(db.orbit(&c01), Some(OrbitClass::GEO));
+        assert_eq!(db.orbit(&c38), Some(OrbitClass::IGSO));
+        assert_eq!(db.block(&e11), Some("IOV"));
+        assert_eq!(db.block(&g04), Some("IIIA"));
+        assert_eq!(
+            db.orbit(&SV::from_str("S23").unwrap()),
+            Some(OrbitClass::GEO)
+        );
+
+        db.update("# launch\nG04 MEO IIIF\n").unwrap();
+        assert_eq!(db.block(&g04), Some("IIIF"));
+
+        db.remove(&g04);
+        assert!(db.info(&g04).is_none());
+
+        assert!(db.update("G04 HEO IIIA").is_err());
+        assert!(db.update("G04 MEO").is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! Serialization schema of the processing types, available on `serde` crate feature.
+//!
+//! Our serialized form does not follow the Rust layout strictly, but is meant to remain stable
+//! and human readable (JSON, TOML..). Epochs, durations, timescales, [SV](gnss_rs::prelude::SV)s
+//! and [Constellation](gnss_rs::prelude::Constellation)s are always described as strings,
+//! using the same formats as our filter descriptors.
+//!
+//! - `MaskOperand`: one of `">"`, `">="`, `"<"`, `"<="`, `"="`, `"!="`, `"in"`, `"!in"`.
+//! - `FilterItem`: single entry map, for example:
+//!   - `{"epoch": "2020-01-01T00:00:00 UTC"}`
+//!   - `{"duration": "1 h"}`
+//!   - `{"snr": 35.0}`, `{"elevation": 10.0}`, `{"azimuth": 120.0}`
+//!   - `{"epoch_range": "2020-01-01T00:00:00 UTC..2020-01-01T06:00:00 UTC"}`
+//!   - `{"relative_epoch": "start+30 min"}`, `{"relative_span": "last:2 h"}`, see [TimeSpan](crate::processing::TimeSpan)
+//!   - `{"snr_template": "template(0:25,15:30,30:35)"}`, see [SNRTemplate](crate::processing::SNRTemplate)
+//!   - `{"snr_range": "30..=45"}`, `{"elevation_range": "10..60"}`, `{"azimuth_range": "(0, 90]"}`
+//!   - `{"sv": ["G08", "E05"]}`. Satellite ranges and wildcards (like `G01-G10,!G05` or `E*`)
+//!     are expanded into the satellites they designate. `C*` and `S*` describe observables,
+//!     unless the `sv` target is used, like `sv=C*,!C01-C05`.
+//!   - `{"constellation": ["GPS", "GAL"]}`
+//!   - `{"selection": {"constellations": ["GAL"], "svs": ["G08"]}}`
+//!   - `{"time_of_day": "22:00 UTC"}`, `{"day_of_week": "sat,sun UTC"}`
+//!   - `{"day_of_year": "045 UTC"}`, `{"time_of_week": "86400 GPST"}`.
+//!     Week numbers, dated days of year and Modified Julian Dates
+//!     (like `week=2310` or `mjd>60000`) are described as epochs or epoch ranges.
+//!   - `{"frequency": 1500.0}`, expressed in MHz
+//!   - `{"region": "circle(48.8,2.3,5km)"}`, see [Region](crate::processing::Region) for other shapes
+//!   - `{"carrier": ["L5", "E5a"]}`
+//!   - `{"marker": ["ABMF", "AJAC"]}`, `{"domes": ["10003M009"]}`
+//!   - `{"cospar": ["1998-017A"]}`, `{"sbas": ["EGNOS", "WAAS"]}`
+//!   - `{"orbit": ["GEO", "IGSO"]}`, `{"block": ["IIIA", "FOC"]}`
+//!   - `{"lli": 1}`, `{"ssi": 5}`, `{"epochflag": ["ok", "powerfailure"]}`
+//!   - `{"nav": {"field": "health", "value": 0.0}}`, the field being one of the
+//!     [NavField](crate::processing::NavField) names, like `iode`, `ura` or `crs`.
+//!     `iode`, `iodc`, `iodnav` and `health` only take positive integer values.
+//!   - `{"nav_range": {"field": "ura", "interval": "0..2"}}`
+//!   - `{"observable": ["L1C", "C1C", "L*"]}`
+//!   - `"clock"`
+//!   - `{"clock_value": {"quantity": "offset", "value": 0.001}}`, where `quantity` is one of
+//!     `offset` (s), `drift` (s/s) or `drift_rate` (s/s²), and `value` is expressed in SI unit
+//!   - `{"complex": ["iode", "crs"]}`
+//! - `MaskFilter`: `{"operand": ">", "item": {"elevation": 10.0}}`
+//! - `DecimationFilterType`: `{"modulo": 10}` or `{"duration": "30 s"}`
+//! - `DecimationFilter`: `{"filter": {"duration": "30 s"}, "item": {"sv": ["G08"]}}`,
+//!   where `item` may be omitted.
+//! - `SmoothingType`: `{"moving_average": "10 min"}` or `"hatch"`
+//! - `SmoothingFilter`: `{"stype": "hatch", "item": {"observable": ["C1C"]}}`,
+//!   where `item` may be omitted.
+//! - `InterpMethod`: `"linear"`, `{"lagrange": 7}` or `"cubic_hermite"`
+//! - `InterpSeries`: `{"epochs": ["2020-01-01T00:00:00 UTC", ...]}` or
+//!   `{"interval": {"interval": "2020-01-01T00:00:00 UTC..2020-01-02T00:00:00 UTC", "period": "30 s"}}`
+//! - `InterpFilter`: `{"method": "linear", "series": {...}, "item": {"observable": ["C1C"]}}`,
+//!   where `item` may be omitted.
+//! - `Filter`: tagged by `type`, for example
+//!   `{"type": "mask", "operand": "=", "item": {"constellation": ["GPS"]}}`
+//!   or `{"type": "decimation", "filter": {"modulo": 2}}`.
+//! - `Repair`: `"zero"`.
+//! - `TimeCorrection`:
+//!   ```json
+//!   {
+//!     "lhs_timescale": "GST",
+//!     "rhs_timescale": "GPST",
+//!     "ref_epoch": "2020-01-01T00:00:00 GPST",
+//!     "validity_period": "1 h",
+//!     "polynomial": {"constant": "1 ns", "rate": "0 ns", "accel": "0 ns"}
+//!   }
+//!   ```
+//!   where the polynomial terms are described like any other duration, and may be negative.
+//! - `TimeCorrectionsDB`: `{"strict_validity": false, "corrections": [...]}`,
+//!   where `strict_validity` may be omitted.
+
+/// (De-)Serializes any type by means of its [std::fmt::Display] and [std::str::FromStr] implementations.
+pub(crate) mod display_fromstr {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::{fmt::Display, str::FromStr};
+
+    pub fn serialize<T: Display, S: Serializer>(value: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let content = String::deserialize(d)?;
+        T::from_str(&content).map_err(D::Error::custom)
+    }
+}
+
+/// (De-)Serializes a list by means of [std::fmt::Display] and [std::str::FromStr] implementations.
+pub(crate) mod display_fromstr_vec {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error, ser::SerializeSeq};
+    use std::{fmt::Display, str::FromStr};
+
+    pub fn serialize<T: Display, S: Serializer>(values: &[T], s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(values.len()))?;
+        for value in values {
+            seq.serialize_element(&value.to_string())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<Vec<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let content = Vec::<String>::deserialize(d)?;
+        content
+            .iter()
+            .map(|value| T::from_str(value).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+/// (De-)Serializes a [Duration](hifitime::Duration), that may be negative.
+pub(crate) mod duration {
+    use hifitime::Duration;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(dt: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(dt)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        let content = String::deserialize(d)?;
+        let content = content.trim();
+        match content.strip_prefix('-') {
+            Some(abs) => Duration::from_str(abs.trim())
+                .map(|dt| -dt)
+                .map_err(D::Error::custom),
+            None => Duration::from_str(content).map_err(D::Error::custom),
+        }
+    }
+}
+
+/// (De-)Serializes a [Polynomial](hifitime::Polynomial), each term being described as a [Duration](hifitime::Duration).
+pub(crate) mod polynomial {
+    use hifitime::{Duration, Polynomial};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Terms {
+        #[serde(with = "super::duration")]
+        constant: Duration,
+        #[serde(with = "super::duration")]
+        rate: Duration,
+        #[serde(with = "super::duration")]
+        accel: Duration,
+    }
+
+    pub fn serialize<S: Serializer>(polynomial: &Polynomial, s: S) -> Result<S::Ok, S::Error> {
+        Terms {
+            constant: polynomial.constant,
+            rate: polynomial.rate,
+            accel: polynomial.accel,
+        }
+        .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Polynomial, D::Error> {
+        let terms = Terms::deserialize(d)?;
+        Ok(Polynomial {
+            constant: terms.constant,
+            rate: terms.rate,
+            accel: terms.accel,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        DecimationFilter, Filter, FilterItem, MaskOperand, NavField, NavValue, Repair,
+        TimeCorrection, TimeCorrectionsDB,
+    };
+    use hifitime::{Duration, Epoch, Polynomial, TimeScale};
+    use std::str::FromStr;
+
+    #[test]
+    fn filter_json_round_trip() {
+        for descriptor in [
+            "GPS",
+            "!=G08,G09",
+            "e>10",
+            "a<=120",
+            "snr>=35",
+            ">start+30min",
+            "last:2h",
+            "snr>template(0:25,15:30,30:35)",
+            "snr>=table(L5,0:20,30:32)",
+            "clk",
+            "=L1C,C1C",
+            "carrier=L5,E5a",
+            "carrier=L1",
+            "freq>1500",
+            "tod>=22:00 UTC",
+            "dow!=sat,sun",
+            "region=circle(48.8,2.3,5km)",
+            "marker=ABMF,AJAC",
+            "domes=10003M009",
+            "cospar=1998-017A",
+            "sbas=EGNOS,WAAS",
+            "health!=0",
+            "ura<=2.0",
+            "ura in 0..2",
+            "lli!=0",
+            "ssi>=5",
+            "epochflag=ok,powerfailure",
+            "orbit!=GEO",
+            "block=IIIA,FOC",
+            "clk>1ms",
+            "clkdrift<1e-9",
+            "week=2310",
+            "doy=045",
+            "tow<86400",
+            ">2020-01-14T00:31:55 UTC",
+            "=1 h",
+            "e in 10..60",
+            "G08,GAL,E05",
+            "snr !in [30,45]",
+            "2020-01-01T00:00:00 UTC..2020-01-01T06:00:00 UTC",
+            "decim:10",
+            "decim:30 s:G08",
+            "smooth:hatch:C1C",
+            "smooth:mov:10 min",
+            "interp:hermite:2020-01-01T00:00:00 UTC..2020-01-01T01:00:00 UTC/30 s:C1C",
+        ] {
+            let filter = Filter::from_str(descriptor).unwrap();
+            let json = serde_json::to_string(&filter).unwrap();
+            let parsed: Filter = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, filter, "json round trip failed for \"{}\"", json);
+        }
+    }
+
+    #[test]
+    fn filter_json_schema() {
+        let filter: Filter = serde_json::from_str(
+            r#"{"type": "mask", "operand": ">=", "item": {"elevation": 10.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            Filter::mask(MaskOperand::GreaterEquals, FilterItem::ElevationItem(10.0))
+        );
+
+        let filter: Filter = serde_json::from_str(
+            r#"{"type": "decimation", "filter": {"duration": "30 s"}, "item": {"sv": ["G08"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            Filter::Decimation(
+                DecimationFilter::duration(Duration::from_seconds(30.0))
+                    .with_item(FilterItem::from_str("G08").unwrap())
+            )
+        );
+
+        let repair: Repair = serde_json::from_str("\"zero\"").unwrap();
+        assert!(matches!(repair, Repair::Zero));
+
+        // integer navigation fields are validated
+        for item in [
+            r#"{"nav": {"field": "iode", "value": 1.5}}"#,
+            r#"{"nav": {"field": "health", "value": -1.0}}"#,
+            r#"{"nav_range": {"field": "iodc", "interval": "0.5..2"}}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<FilterItem>(item).is_err(),
+                "deserialized invalid item {}",
+                item
+            );
+        }
+        let item: FilterItem =
+            serde_json::from_str(r#"{"nav": {"field": "iode", "value": 12.0}}"#).unwrap();
+        assert_eq!(
+            item,
+            FilterItem::NavItem(NavValue::new(NavField::IODE, 12.0).unwrap())
+        );
+    }
+
+    #[test]
+    fn time_corrections_db_json() {
+        let mut db = TimeCorrectionsDB::default();
+
+        db.add(TimeCorrection {
+            lhs_timescale: TimeScale::GST,
+            rhs_timescale: TimeScale::GPST,
+            ref_epoch: Epoch::from_str("2020-01-01T00:00:00 GPST").unwrap(),
+            validity_period: Duration::from_hours(1.0),
+            polynomial: Polynomial {
+                constant: Duration::from_seconds(-1.0E-9),
+                rate: Duration::ZERO,
+                accel: Duration::ZERO,
+            },
+        });
+
+        let json = serde_json::to_string(&db).unwrap();
+        let parsed: TimeCorrectionsDB = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+        let db: TimeCorrectionsDB = serde_json::from_str(
+            r#"{
+                "corrections": [{
+                    "lhs_timescale": "GST",
+                    "rhs_timescale": "GPST",
+                    "ref_epoch": "2020-01-01T00:00:00 GPST",
+                    "validity_period": "1 h",
+                    "polynomial": {"constant": "-1 ns", "rate": "0 ns", "accel": "0 ns"}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let t_gst = Epoch::from_str("2020-01-01T00:00:00 GST").unwrap();
+        let t_gpst = db.precise_epoch_correction(t_gst, TimeScale::GPST).unwrap();
+        assert_eq!(t_gpst.time_scale, TimeScale::GPST);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::processing::{FilterItem, ItemError};
+use hifitime::Duration;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Supported Smoothing Filters
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SmoothingType {
+    /// Moving average filter
+    #[cfg_attr(feature = "serde", serde(with = "crate::processing::schema::duration"))]
+    MovingAverage(Duration),
+    /// Hatch filter: Pseudo range specific smoothing method
+    Hatch,
+}
+
+/// Smoothing Filter to smooth data subsets
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SmoothingFilter {
+    /// Type of smoothing to apply
+    pub stype: SmoothingType,
+    /// Possible targeted subset to narrow down filter's application.
+    /// When undefined, the filter applies to entire dataset
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub item: Option<FilterItem>,
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid description \"{0}\"")]
+    InvalidDescription(String),
+    #[error("unknown smoothing filter \"{0}\"")]
+    UnknownFilter(String),
+    #[error("invalid smoothed item")]
+    InvalidItem(#[from] ItemError),
+    #[error("failed to parse duration \"{0}\"")]
+    DurationParsing(String),
+}
+
+impl SmoothingFilter {
+    /// Builds a new Moving Average filter
+    pub fn moving_average(window: Duration) -> Self {
+        Self {
+            item: None,
+            stype: SmoothingType::MovingAverage(window),
+        }
+    }
+    /// Builds a new Hatch filter
+    pub fn hatch() -> Self {
+        Self {
+            item: None,
+            stype: SmoothingType::Hatch,
+        }
+    }
+    /// Adds targetted item to be smoothed
+    pub fn with_item(&self, item: FilterItem) -> Self {
+        let mut s = self.clone();
+        s.item = Some(item.clone());
+        s
+    }
+}
+
+impl std::str::FromStr for SmoothingFilter {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let items: Vec<&str> = content.trim().split(':').collect();
+        if items[0].trim().eq("hatch") {
+            Ok(Self {
+                item: {
+                    if items.len() > 1 {
+                        let item = FilterItem::from_str(items[1].trim())?;
+                        Some(item)
+                    } else {
+                        None // no subset description
+                    }
+                },
+                stype: SmoothingType::Hatch,
+            })
+        } else if items[0].trim().eq("mov") {
+            if items.len() < 2 {
+                return Err(Error::InvalidDescription(format!("{:?}", items)));
+            }
+            let dt = Duration::from_str(items[1].trim())
+                .map_err(|_| Error::DurationParsing(items[1].trim().to_string()))?;
+            Ok(Self {
+                item: {
+                    if items.len() > 2 {
+                        let item = FilterItem::from_str(items[2].trim())?;
+                        Some(item)
+                    } else {
+                        None // no data subset
+                    }
+                },
+                stype: SmoothingType::MovingAverage(dt),
+            })
+        } else {
+            Err(Error::UnknownFilter(items[0].to_string()))
+        }
+    }
+}
+
+impl std::fmt::Display for SmoothingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::MovingAverage(dt) => write!(f, "mov:{}", dt),
+            Self::Hatch => write!(f, "hatch"),
+        }
+    }
+}
+
+impl std::fmt::Display for SmoothingFilter {
+    /// Formats this [SmoothingFilter] so it may be parsed back with [SmoothingFilter::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.stype)?;
+        if let Some(item) = &self.item {
+            write!(f, ":{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+/// The [Smooth] trait is implemented to smooth data subsets prior analysis.
+pub trait Smooth {
+    /// Immutable smoothing
+    fn smooth(&self, f: &SmoothingFilter) -> Self;
+    /// Mutable smoothing
+    fn smooth_mut(&mut self, f: &SmoothingFilter);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn from_str() {
+        for desc in ["hatch", "hatch:C1C", "hatch:c1c,c2p"] {
+            let filter = SmoothingFilter::from_str(desc);
+            assert!(
+                filter.is_ok(),
+                "smoothing_filter::from_str() failed on \"{}\"",
+                desc
+            );
+        }
+        for desc in [
+            "mov:10 min",
+            "mov:1 hour",
+            "mov:10 min:clk",
+            "mov:10 hour:clk",
+        ] {
+            let filter = SmoothingFilter::from_str(desc);
+            assert!(
+                filter.is_ok(),
+                "smoothing_filter::from_str() failed on \"{}\"",
+                desc
+            );
+        }
+        assert_eq!(
+            SmoothingFilter::from_str("mov:10 min:clk").unwrap(),
+            SmoothingFilter::moving_average(Duration::from_str("10 min").unwrap())
+                .with_item(FilterItem::ClockItem)
+        );
+        for desc in ["mov", "mov:ten", "avg:10 min"] {
+            assert!(
+                SmoothingFilter::from_str(desc).is_err(),
+                "parsed invalid smoothing filter \"{}\"",
+                desc
+            );
+        }
+    }
+}
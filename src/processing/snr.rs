@@ -0,0 +1,244 @@
+//! Elevation dependent SNR thresholds
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::processing::{Carrier, CarrierError};
+
+/// [SNRTemplate] parsing errors
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid snr template \"{0}\"")]
+    InvalidTemplate(String),
+    #[error("invalid template point \"{0}\"")]
+    InvalidPoint(String),
+    #[error("template elevations should increase within [0, 90]")]
+    InvalidElevations,
+    #[error("snr template should describe at least one point")]
+    NoPoints,
+    #[error("invalid carrier: {0}")]
+    Carrier(#[from] CarrierError),
+}
+
+/// How thresholds are resolved between the points of a [SNRTemplate]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TemplateInterpolation {
+    /// Linear interpolation between surrounding points
+    #[default]
+    Linear,
+    /// Tabulated: threshold of the closest point below (in elevation)
+    Step,
+}
+
+impl TemplateInterpolation {
+    /// Returns the keyword of this [TemplateInterpolation]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "template",
+            Self::Step => "table",
+        }
+    }
+}
+
+/// [SNRTemplate] is a SNR threshold curve, function of the elevation angle,
+/// described by `elevation:snr` points (in degrees and dB), sorted by elevation:
+/// - `template(0:25,15:30,30:35)`: thresholds are linearly interpolated between points
+/// - `table(0:25,15:30,30:35)`: tabulated thresholds, each point applying until the next one.
+///
+/// Thresholds are held constant below the first point and past the last one.
+/// The template may be restricted to one carrier band by prefixing the points,
+/// like `template(L5,0:20,30:32)`: other signals are not compared.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct SNRTemplate {
+    /// (elevation, snr) points, in degrees and dB, sorted by elevation
+    points: Vec<(f64, f64)>,
+    /// [TemplateInterpolation] between points
+    pub interpolation: TemplateInterpolation,
+    /// [Carrier] band this template applies to, all signals when omitted
+    pub carrier: Option<Carrier>,
+}
+
+impl SNRTemplate {
+    /// Builds a new [SNRTemplate] applying to all signals, from (elevation, snr) points,
+    /// in degrees and dB. There should be at least one point, and elevations should
+    /// increase within [0, 90].
+    pub fn new(
+        points: Vec<(f64, f64)>,
+        interpolation: TemplateInterpolation,
+    ) -> Result<Self, Error> {
+        if points.is_empty() {
+            return Err(Error::NoPoints);
+        }
+        let increasing = points.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        let within = points.iter().all(|(e, _)| (0.0..=90.0).contains(e));
+        if !increasing || !within {
+            return Err(Error::InvalidElevations);
+        }
+        Ok(Self {
+            points,
+            interpolation,
+            carrier: None,
+        })
+    }
+
+    /// Restricts this [SNRTemplate] to given [Carrier] band
+    pub fn with_carrier(&self, carrier: Carrier) -> Self {
+        let mut s = self.clone();
+        s.carrier = Some(carrier);
+        s
+    }
+
+    /// Returns the (elevation, snr) points of this [SNRTemplate], sorted by elevation
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Returns the SNR threshold (in dB) at given elevation angle (in degrees)
+    pub fn threshold(&self, elevation: f64) -> f64 {
+        let below = self
+            .points
+            .iter()
+            .rposition(|(e, _)| *e <= elevation)
+            .unwrap_or(0);
+        let (e0, snr0) = self.points[below];
+        match (self.interpolation, self.points.get(below + 1)) {
+            (TemplateInterpolation::Linear, Some((e1, snr1))) if elevation > e0 => {
+                snr0 + (snr1 - snr0) * (elevation - e0) / (e1 - e0)
+            }
+            _ => snr0,
+        }
+    }
+
+    /// Returns true if this [SNRTemplate] applies to given [Carrier]
+    pub fn applies_to(&self, carrier: &Carrier) -> bool {
+        match &self.carrier {
+            Some(band) => band.shares_frequency(carrier),
+            None => true,
+        }
+    }
+}
+
+/// Parses one `elevation:snr` point
+fn parse_point(content: &str) -> Result<(f64, f64), Error> {
+    let c = content.trim();
+    let invalid = || Error::InvalidPoint(c.to_string());
+    let (elevation, snr) = c.split_once(':').ok_or_else(invalid)?;
+    let elevation = f64::from_str(elevation.trim()).map_err(|_| invalid())?;
+    let snr = f64::from_str(snr.trim()).map_err(|_| invalid())?;
+    Ok((elevation, snr))
+}
+
+impl FromStr for SNRTemplate {
+    type Err = Error;
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let c = content.trim();
+        let (keyword, parameters) = c
+            .strip_suffix(')')
+            .and_then(|c| c.split_once('('))
+            .ok_or(Error::InvalidTemplate(c.to_string()))?;
+
+        let interpolation = match keyword.trim().to_ascii_lowercase().as_str() {
+            "template" => TemplateInterpolation::Linear,
+            "table" => TemplateInterpolation::Step,
+            _ => return Err(Error::InvalidTemplate(c.to_string())),
+        };
+
+        let mut tokens = parameters.split(',').peekable();
+        let carrier = match tokens.peek() {
+            Some(token) if !token.contains(':') => Some(Carrier::from_str(token)?),
+            _ => None,
+        };
+        if carrier.is_some() {
+            tokens.next();
+        }
+
+        let points = tokens.map(parse_point).collect::<Result<Vec<_>, _>>()?;
+        let template = Self::new(points, interpolation)?;
+        Ok(Self {
+            carrier,
+            ..template
+        })
+    }
+}
+
+impl std::fmt::Display for SNRTemplate {
+    /// Formats this [SNRTemplate] so it may be parsed back with [SNRTemplate::from_str].
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut parameters = self
+            .points
+            .iter()
+            .map(|(elevation, snr)| format!("{}:{}", elevation, snr))
+            .collect::<Vec<_>>();
+        if let Some(carrier) = &self.carrier {
+            parameters.insert(0, carrier.to_string());
+        }
+        write!(f, "{}({})", self.interpolation.name(), parameters.join(","))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snr_templates() {
+        let template = SNRTemplate::from_str("template(0:25, 15:30, 30:35)").unwrap();
+        assert_eq!(template.interpolation, TemplateInterpolation::Linear);
+        assert_eq!(template.carrier, None);
+        for (elevation, expected) in [
+            (0.0, 25.0),
+            (7.5, 27.5),
+            (15.0, 30.0),
+            (20.0, 31.666666666666668),
+            (45.0, 35.0),
+        ] {
+            let threshold = template.threshold(elevation);
+            assert!(
+                (threshold - expected).abs() < 1.0E-9,
+                "threshold {} at {}°",
+                threshold,
+                elevation
+            );
+        }
+
+        let table = SNRTemplate::from_str("TABLE(L5,5:20,30:32)").unwrap();
+        assert_eq!(table.interpolation, TemplateInterpolation::Step);
+        assert_eq!(table.carrier, Some(Carrier::L5));
+        assert_eq!(table.threshold(0.0), 20.0);
+        assert_eq!(table.threshold(29.9), 20.0);
+        assert_eq!(table.threshold(60.0), 32.0);
+        assert!(table.applies_to(&Carrier::L5));
+        assert!(!table.applies_to(&Carrier::L1));
+
+        assert_eq!(
+            SNRTemplate::new(vec![(5.0, 20.0), (30.0, 32.0)], TemplateInterpolation::Step)
+                .unwrap()
+                .with_carrier(Carrier::L5),
+            table
+        );
+        assert!(SNRTemplate::new(vec![], TemplateInterpolation::Linear).is_err());
+        assert!(
+            SNRTemplate::new(
+                vec![(30.0, 25.0), (15.0, 30.0)],
+                TemplateInterpolation::Linear
+            )
+            .is_err()
+        );
+
+        for template in [template, table] {
+            let parsed = SNRTemplate::from_str(&template.to_string()).unwrap();
+            assert_eq!(parsed, template);
+        }
+
+        for desc in [
+            "template()",
+            "template(0:25,0:30)",
+            "template(30:25,15:30)",
+            "template(0:25,95:30)",
+            "template(0-25)",
+            "template(X9,0:25)",
+            "curve(0:25)",
+        ] {
+            assert!(SNRTemplate::from_str(desc).is_err(), "parsed \"{}\"", desc);
+        }
+    }
+}
@@ -3,6 +3,9 @@ use hifitime::{Duration, Epoch, Polynomial, TimeScale};
 #[cfg(feature = "python")]
 use pyo3::prelude::pyclass;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 #[cfg(feature = "python")]
 use pyo3::{PyResult, pymethods};
 
@@ -11,20 +14,38 @@ use pyo3::{PyResult, pymethods};
 #[derive(Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "python", pyclass)]
 #[cfg_attr(feature = "python", pyo3(module = "qc_traits"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TimeCorrection {
     /// LHS [TimeScale] to which [Polynomial] applies
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::display_fromstr")
+    )]
     pub lhs_timescale: TimeScale,
 
     /// RHS [TimeScale] to which [Polynomial] applies
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::display_fromstr")
+    )]
     pub rhs_timescale: TimeScale,
 
     /// Reference [Epoch] usually expressed in LHS [TimeScale], but we support any [TimeScale] here.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::display_fromstr")
+    )]
     pub ref_epoch: Epoch,
 
     /// Validity period as [Duration]
+    #[cfg_attr(feature = "serde", serde(with = "crate::processing::schema::duration"))]
     pub validity_period: Duration,
 
     /// [Polynomial]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::processing::schema::polynomial")
+    )]
     pub polynomial: Polynomial,
 }
 
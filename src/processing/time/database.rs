@@ -11,6 +11,9 @@ use super::Timeshift;
 #[cfg(feature = "python")]
 use pyo3::prelude::pyclass;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// [TimeCorrectionsDB] is a [TimeCorrection]s database used by [TimeScale]
 /// monitoring applications and applications that need exact [TimeScale] states at all times.
 /// Our [Timeshift] trait uses it in the precise conversion method.
@@ -23,10 +26,12 @@ use pyo3::prelude::pyclass;
 #[derive(Default, Clone)]
 #[cfg_attr(feature = "python", pyclass)]
 #[cfg_attr(feature = "python", pyo3(module = "qc_traits"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TimeCorrectionsDB {
     /// When strict validity is asserted, we will respect
     /// the corrections validity strictly. Otherwise, the last
     /// correction available may be used (propagated) in the future.
+    #[cfg_attr(feature = "serde", serde(default))]
     strict_validity: bool,
 
     /// [TimeCorrection] database